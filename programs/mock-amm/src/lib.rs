@@ -0,0 +1,373 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, MintTo, Token, TokenAccount, Transfer},
+};
+
+// NOTE: This must match the program ID used when deploying (see Anchor.toml)
+declare_id!("3BRYigbjj6tFKFnBRDTR5ynUiE7N1wPkZdTg8yj2zq2w");
+
+/// LP mint decimals used by PumpSwap and Raydium CPMM pools
+pub const LP_DECIMALS: u8 = 9;
+
+/// Seed of Raydium CPMM's vault and LP mint authority PDA
+pub const CPMM_AUTHORITY_SEED: &[u8] = b"vault_and_lp_mint_auth_seed";
+
+/// Local stand-in for the PumpSwap and Raydium CPMM AMMs, used by the integration tests.
+/// `create_pool` takes the same instruction data and accounts (in the same order) as
+/// PumpSwap; the global config, Token-2022 and events are skipped and the LP mint is a
+/// plain SPL mint. `initialize` does the same for Raydium CPMM; the AMM config, pool
+/// creation fee and observation state are skipped. The initial LP supply is
+/// sqrt(base_amount * quote_amount) (sqrt(amount_0 * amount_1) for CPMM).
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    /// Create a pool, deposit both sides and mint the initial LP supply to the creator
+    pub fn create_pool(
+        ctx: Context<CreatePool>,
+        index: u16,
+        base_amount_in: u64,
+        quote_amount_in: u64,
+        coin_creator: Pubkey,
+    ) -> Result<()> {
+        require!(
+            base_amount_in > 0 && quote_amount_in > 0,
+            MockAmmError::ZeroAmount
+        );
+
+        for (from, to, amount) in [
+            (
+                &ctx.accounts.user_base_token_account,
+                &ctx.accounts.pool_base_token_account,
+                base_amount_in,
+            ),
+            (
+                &ctx.accounts.user_quote_token_account,
+                &ctx.accounts.pool_quote_token_account,
+                quote_amount_in,
+            ),
+        ] {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.creator.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        let lp_supply = u64::try_from(isqrt(base_amount_in as u128 * quote_amount_in as u128))
+            .map_err(|_| MockAmmError::ZeroAmount)?;
+
+        let creator_key = ctx.accounts.creator.key();
+        let base_mint_key = ctx.accounts.base_mint.key();
+        let quote_mint_key = ctx.accounts.quote_mint.key();
+        let index_bytes = index.to_le_bytes();
+        let pool_seeds: &[&[u8]] = &[
+            b"pool",
+            &index_bytes,
+            creator_key.as_ref(),
+            base_mint_key.as_ref(),
+            quote_mint_key.as_ref(),
+            &[ctx.bumps.pool],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.user_pool_token_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            lp_supply,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.index = index;
+        pool.creator = creator_key;
+        pool.base_mint = base_mint_key;
+        pool.quote_mint = quote_mint_key;
+        pool.lp_mint = ctx.accounts.lp_mint.key();
+        pool.lp_supply = lp_supply;
+        pool.coin_creator = coin_creator;
+
+        Ok(())
+    }
+
+    /// Raydium CPMM: create a pool for the (address-ordered) token_0/token_1 pair, deposit
+    /// both sides into the pool vaults and mint the initial LP supply to the creator
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+    ) -> Result<()> {
+        require!(
+            init_amount_0 > 0 && init_amount_1 > 0,
+            MockAmmError::ZeroAmount
+        );
+        require!(
+            ctx.accounts.token_0_mint.key() < ctx.accounts.token_1_mint.key(),
+            MockAmmError::InvalidMintOrder
+        );
+
+        for (from, to, amount) in [
+            (
+                &ctx.accounts.creator_token_0,
+                &ctx.accounts.token_0_vault,
+                init_amount_0,
+            ),
+            (
+                &ctx.accounts.creator_token_1,
+                &ctx.accounts.token_1_vault,
+                init_amount_1,
+            ),
+        ] {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.creator.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        let lp_supply = u64::try_from(isqrt(init_amount_0 as u128 * init_amount_1 as u128))
+            .map_err(|_| MockAmmError::ZeroAmount)?;
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.creator_lp_token.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+                &[&[CPMM_AUTHORITY_SEED, &[ctx.bumps.authority]]],
+            ),
+            lp_supply,
+        )?;
+
+        let pool_state = &mut ctx.accounts.pool_state;
+        pool_state.amm_config = ctx.accounts.amm_config.key();
+        pool_state.pool_creator = ctx.accounts.creator.key();
+        pool_state.token_0_mint = ctx.accounts.token_0_mint.key();
+        pool_state.token_1_mint = ctx.accounts.token_1_mint.key();
+        pool_state.lp_mint = ctx.accounts.lp_mint.key();
+        pool_state.lp_supply = lp_supply;
+        pool_state.open_time = open_time;
+
+        Ok(())
+    }
+}
+
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+#[account]
+pub struct Pool {
+    pub index: u16,
+    pub creator: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_supply: u64,
+    pub coin_creator: Pubkey,
+}
+
+impl Pool {
+    pub const LEN: usize = 2 + 32 * 4 + 8 + 32;
+}
+
+#[account]
+pub struct PoolState {
+    pub amm_config: Pubkey,
+    pub pool_creator: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_supply: u64,
+    pub open_time: u64,
+}
+
+impl PoolState {
+    pub const LEN: usize = 32 * 5 + 8 + 8;
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct CreatePool<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Pool::LEN,
+        seeds = [
+            b"pool",
+            index.to_le_bytes().as_ref(),
+            creator.key().as_ref(),
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    /// CHECK: Global config (unused by the mock)
+    pub global_config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub base_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"pool_lp_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = LP_DECIMALS,
+        mint::authority = pool,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(mut, token::mint = base_mint, token::authority = creator)]
+    pub user_base_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = quote_mint, token::authority = creator)]
+    pub user_quote_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = lp_mint,
+        associated_token::authority = creator,
+    )]
+    pub user_pool_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = base_mint,
+        associated_token::authority = pool,
+    )]
+    pub pool_base_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = quote_mint,
+        associated_token::authority = pool,
+    )]
+    pub pool_quote_token_account: Box<Account<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Token-2022 program (unused by the mock)
+    pub token_2022_program: UncheckedAccount<'info>,
+    /// Base token program (the mock only supports SPL token)
+    pub token_program: Program<'info, Token>,
+    /// CHECK: Quote token program (same SPL token program in the mock)
+    pub quote_token_program: UncheckedAccount<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: Event authority (unused by the mock)
+    pub event_authority: UncheckedAccount<'info>,
+    /// CHECK: This program
+    pub program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: AMM config (unused by the mock beyond the pool address)
+    pub amm_config: UncheckedAccount<'info>,
+    #[account(seeds = [CPMM_AUTHORITY_SEED], bump)]
+    /// CHECK: Vault and LP mint authority PDA
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + PoolState::LEN,
+        seeds = [
+            b"pool",
+            amm_config.key().as_ref(),
+            token_0_mint.key().as_ref(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,
+    pub token_0_mint: Box<Account<'info, Mint>>,
+    pub token_1_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"pool_lp_mint", pool_state.key().as_ref()],
+        bump,
+        mint::decimals = LP_DECIMALS,
+        mint::authority = authority,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(mut, token::mint = token_0_mint, token::authority = creator)]
+    pub creator_token_0: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_1_mint, token::authority = creator)]
+    pub creator_token_1: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = lp_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_lp_token: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"pool_vault", pool_state.key().as_ref(), token_0_mint.key().as_ref()],
+        bump,
+        token::mint = token_0_mint,
+        token::authority = authority,
+    )]
+    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"pool_vault", pool_state.key().as_ref(), token_1_mint.key().as_ref()],
+        bump,
+        token::mint = token_1_mint,
+        token::authority = authority,
+    )]
+    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    /// CHECK: Pool creation fee receiver (unused by the mock)
+    pub create_pool_fee: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: Oracle observation state (unused by the mock)
+    pub observation_state: UncheckedAccount<'info>,
+    /// LP token program (the mock only supports SPL token)
+    pub token_program: Program<'info, Token>,
+    /// CHECK: token_0 program (same SPL token program in the mock)
+    pub token_0_program: UncheckedAccount<'info>,
+    /// CHECK: token_1 program (same SPL token program in the mock)
+    pub token_1_program: UncheckedAccount<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[error_code]
+pub enum MockAmmError {
+    #[msg("Both pool sides need a deposit")]
+    ZeroAmount,
+    #[msg("token_0_mint must sort before token_1_mint")]
+    InvalidMintOrder,
+}
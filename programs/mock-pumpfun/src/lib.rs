@@ -0,0 +1,224 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, MintTo, Token, TokenAccount, Transfer},
+};
+
+// NOTE: This must match the program ID used when deploying (see Anchor.toml)
+declare_id!("7uwzfMVNFswtXjq55ha1L2fAqjWPVa2aTDzcLWRjVXBD");
+
+/// Token decimals used by pump.fun mints
+pub const TOKEN_DECIMALS: u8 = 6;
+/// Initial curve state, same numbers as pump.fun
+pub const TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+pub const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
+pub const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30_000_000_000;
+pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+
+/// Local stand-in for the pump.fun bonding curve program, used by the integration tests.
+/// `create` and `buy` take the same instruction data and accounts (in the same order) as
+/// pump.fun; metadata, fees and events are skipped and the price is a plain
+/// constant-product curve over the virtual reserves.
+#[program]
+pub mod mock_pumpfun {
+    use super::*;
+
+    /// Create the mint and its bonding curve, minting the whole supply to the curve
+    pub fn create(
+        ctx: Context<Create>,
+        _name: String,
+        _symbol: String,
+        _uri: String,
+        creator: Pubkey,
+    ) -> Result<()> {
+        let mint_authority_seeds: &[&[u8]] = &[b"mint-authority", &[ctx.bumps.mint_authority]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.associated_bonding_curve.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                &[mint_authority_seeds],
+            ),
+            TOKEN_TOTAL_SUPPLY,
+        )?;
+
+        let bonding_curve = &mut ctx.accounts.bonding_curve;
+        bonding_curve.virtual_token_reserves = INITIAL_VIRTUAL_TOKEN_RESERVES;
+        bonding_curve.virtual_sol_reserves = INITIAL_VIRTUAL_SOL_RESERVES;
+        bonding_curve.real_token_reserves = INITIAL_REAL_TOKEN_RESERVES;
+        bonding_curve.real_sol_reserves = 0;
+        bonding_curve.token_total_supply = TOKEN_TOTAL_SUPPLY;
+        bonding_curve.complete = false;
+        bonding_curve.creator = creator;
+
+        Ok(())
+    }
+
+    /// Buy `amount` tokens for at most `max_sol_cost` lamports
+    pub fn buy(ctx: Context<Buy>, amount: u64, max_sol_cost: u64) -> Result<()> {
+        let bonding_curve = &ctx.accounts.bonding_curve;
+        require!(!bonding_curve.complete, MockPumpfunError::CurveComplete);
+        require!(
+            amount > 0 && amount <= bonding_curve.real_token_reserves,
+            MockPumpfunError::InsufficientReserves
+        );
+
+        // sol_cost = ceil(amount * virtual_sol / (virtual_token - amount))
+        let numerator = (amount as u128) * (bonding_curve.virtual_sol_reserves as u128);
+        let denominator = (bonding_curve.virtual_token_reserves - amount) as u128;
+        let sol_cost = u64::try_from(numerator.div_ceil(denominator))
+            .map_err(|_| MockPumpfunError::InsufficientReserves)?;
+        require!(
+            sol_cost <= max_sol_cost,
+            MockPumpfunError::TooMuchSolRequired
+        );
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.bonding_curve.to_account_info(),
+                },
+            ),
+            sol_cost,
+        )?;
+
+        let mint_key = ctx.accounts.mint.key();
+        let bonding_curve_seeds: &[&[u8]] = &[
+            b"bonding-curve",
+            mint_key.as_ref(),
+            &[ctx.bumps.bonding_curve],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.associated_bonding_curve.to_account_info(),
+                    to: ctx.accounts.associated_user.to_account_info(),
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                },
+                &[bonding_curve_seeds],
+            ),
+            amount,
+        )?;
+
+        let bonding_curve = &mut ctx.accounts.bonding_curve;
+        bonding_curve.virtual_token_reserves -= amount;
+        bonding_curve.real_token_reserves -= amount;
+        bonding_curve.virtual_sol_reserves += sol_cost;
+        bonding_curve.real_sol_reserves += sol_cost;
+        bonding_curve.complete = bonding_curve.real_token_reserves == 0;
+
+        Ok(())
+    }
+}
+
+#[account]
+pub struct BondingCurve {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    pub complete: bool,
+    pub creator: Pubkey,
+}
+
+impl BondingCurve {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 1 + 32;
+}
+
+#[derive(Accounts)]
+pub struct Create<'info> {
+    #[account(
+        init,
+        payer = user,
+        mint::decimals = TOKEN_DECIMALS,
+        mint::authority = mint_authority,
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(seeds = [b"mint-authority"], bump)]
+    /// CHECK: Mint authority PDA
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + BondingCurve::LEN,
+        seeds = [b"bonding-curve", mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub associated_bonding_curve: Account<'info, TokenAccount>,
+    /// CHECK: Global config (unused by the mock)
+    pub global: UncheckedAccount<'info>,
+    /// CHECK: Metaplex program (unused by the mock)
+    pub mpl_token_metadata: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: Metadata account (unused by the mock)
+    pub metadata: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    /// CHECK: Event authority (unused by the mock)
+    pub event_authority: UncheckedAccount<'info>,
+    /// CHECK: This program
+    pub program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    /// CHECK: Global config (unused by the mock)
+    pub global: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: Fee recipient (unused by the mock)
+    pub fee_recipient: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"bonding-curve", mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub associated_bonding_curve: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub associated_user: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    /// CHECK: Creator fee vault (unused by the mock)
+    pub creator_vault: UncheckedAccount<'info>,
+    /// CHECK: Event authority (unused by the mock)
+    pub event_authority: UncheckedAccount<'info>,
+    /// CHECK: This program
+    pub program: UncheckedAccount<'info>,
+}
+
+#[error_code]
+pub enum MockPumpfunError {
+    #[msg("Bonding curve is complete")]
+    CurveComplete,
+    #[msg("Not enough tokens left on the curve")]
+    InsufficientReserves,
+    #[msg("Slippage: more SOL required than max_sol_cost")]
+    TooMuchSolRequired,
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::errors::PresaleError;
+use crate::state::data::AmmKind;
+use super::{pumpswap, raydium_cpmm};
+
+/// Accounts and amounts every AMM needs to seed a pool.
+/// `creator` is the lp_authority PDA: it owns both deposit accounts, pays the pool's rent
+/// and receives the LP tokens.
+pub struct PoolDeposit<'a, 'info> {
+    pub creator: &'a AccountInfo<'info>,
+    pub base_mint: &'a AccountInfo<'info>,
+    pub quote_mint: &'a AccountInfo<'info>,
+    pub creator_base_account: &'a AccountInfo<'info>,
+    pub creator_quote_account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub associated_token_program: &'a AccountInfo<'info>,
+    pub rent: &'a AccountInfo<'info>,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+/// Create the pool on `amm` and return the token account that received the LP tokens.
+/// `pool_accounts` are the AMM-specific accounts (see each adapter for the order),
+/// passed through as remaining accounts.
+pub fn create_pool<'info>(
+    amm: AmmKind,
+    program: &AccountInfo<'info>,
+    deposit: &PoolDeposit<'_, 'info>,
+    pool_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<AccountInfo<'info>> {
+    match amm {
+        AmmKind::PumpSwap => pumpswap::create_pool(program, deposit, pool_accounts, signer_seeds),
+        AmmKind::RaydiumCpmm => {
+            raydium_cpmm::initialize(program, deposit, pool_accounts, signer_seeds)
+        }
+    }
+}
+
+/// Split off the first `N` pool accounts, failing if the caller passed too few
+pub(crate) fn take_accounts<'a, 'info, const N: usize>(
+    pool_accounts: &'a [AccountInfo<'info>],
+) -> Result<&'a [AccountInfo<'info>; N]> {
+    pool_accounts
+        .get(..N)
+        .and_then(|accounts| accounts.try_into().ok())
+        .ok_or_else(|| PresaleError::MissingPoolAccounts.into())
+}
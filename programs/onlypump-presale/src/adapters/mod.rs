@@ -0,0 +1,4 @@
+pub mod amm;
+pub mod pumpfun;
+pub mod pumpswap;
+pub mod raydium_cpmm;
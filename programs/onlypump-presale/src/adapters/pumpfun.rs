@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+/// Mainnet pump.fun program (default for PlatformConfig::pumpfun_program)
+pub const PUMPFUN_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+
+/// Anchor discriminators: sha256("global:create")[..8], sha256("global:buy")[..8]
+const CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+
+#[derive(AnchorSerialize)]
+struct CreateArgs {
+    name: String,
+    symbol: String,
+    uri: String,
+    creator: Pubkey,
+}
+
+#[derive(AnchorSerialize)]
+struct BuyArgs {
+    amount: u64,
+    max_sol_cost: u64,
+}
+
+/// Accounts of pump.fun `create`, in instruction order
+pub struct CreateAccounts<'a, 'info> {
+    pub mint: &'a AccountInfo<'info>,
+    pub mint_authority: &'a AccountInfo<'info>,
+    pub bonding_curve: &'a AccountInfo<'info>,
+    pub associated_bonding_curve: &'a AccountInfo<'info>,
+    pub global: &'a AccountInfo<'info>,
+    pub mpl_token_metadata: &'a AccountInfo<'info>,
+    pub metadata: &'a AccountInfo<'info>,
+    pub user: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub associated_token_program: &'a AccountInfo<'info>,
+    pub rent: &'a AccountInfo<'info>,
+    pub event_authority: &'a AccountInfo<'info>,
+    pub program: &'a AccountInfo<'info>,
+}
+
+/// Accounts of pump.fun `buy`, in instruction order
+pub struct BuyAccounts<'a, 'info> {
+    pub global: &'a AccountInfo<'info>,
+    pub fee_recipient: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub bonding_curve: &'a AccountInfo<'info>,
+    pub associated_bonding_curve: &'a AccountInfo<'info>,
+    pub associated_user: &'a AccountInfo<'info>,
+    pub user: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub creator_vault: &'a AccountInfo<'info>,
+    pub event_authority: &'a AccountInfo<'info>,
+    pub program: &'a AccountInfo<'info>,
+}
+
+/// CPI into pump.fun `create`; `user` pays for the new accounts and signs with `signer_seeds`
+pub fn create<'info>(
+    accounts: &CreateAccounts<'_, 'info>,
+    name: String,
+    symbol: String,
+    uri: String,
+    creator: Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = CREATE_DISCRIMINATOR.to_vec();
+    CreateArgs {
+        name,
+        symbol,
+        uri,
+        creator,
+    }
+    .serialize(&mut data)?;
+
+    let ix = Instruction {
+        program_id: accounts.program.key(),
+        accounts: vec![
+            AccountMeta::new(accounts.mint.key(), true),
+            AccountMeta::new_readonly(accounts.mint_authority.key(), false),
+            AccountMeta::new(accounts.bonding_curve.key(), false),
+            AccountMeta::new(accounts.associated_bonding_curve.key(), false),
+            AccountMeta::new_readonly(accounts.global.key(), false),
+            AccountMeta::new_readonly(accounts.mpl_token_metadata.key(), false),
+            AccountMeta::new(accounts.metadata.key(), false),
+            AccountMeta::new(accounts.user.key(), true),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+            AccountMeta::new_readonly(accounts.associated_token_program.key(), false),
+            AccountMeta::new_readonly(accounts.rent.key(), false),
+            AccountMeta::new_readonly(accounts.event_authority.key(), false),
+            AccountMeta::new_readonly(accounts.program.key(), false),
+        ],
+        data,
+    };
+
+    invoke_signed(
+        &ix,
+        &[
+            accounts.mint.clone(),
+            accounts.mint_authority.clone(),
+            accounts.bonding_curve.clone(),
+            accounts.associated_bonding_curve.clone(),
+            accounts.global.clone(),
+            accounts.mpl_token_metadata.clone(),
+            accounts.metadata.clone(),
+            accounts.user.clone(),
+            accounts.system_program.clone(),
+            accounts.token_program.clone(),
+            accounts.associated_token_program.clone(),
+            accounts.rent.clone(),
+            accounts.event_authority.clone(),
+            accounts.program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// CPI into pump.fun `buy`; tokens go to `associated_user`, SOL comes from `user`
+pub fn buy<'info>(
+    accounts: &BuyAccounts<'_, 'info>,
+    amount: u64,
+    max_sol_cost: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = BUY_DISCRIMINATOR.to_vec();
+    BuyArgs {
+        amount,
+        max_sol_cost,
+    }
+    .serialize(&mut data)?;
+
+    let ix = Instruction {
+        program_id: accounts.program.key(),
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.global.key(), false),
+            AccountMeta::new(accounts.fee_recipient.key(), false),
+            AccountMeta::new_readonly(accounts.mint.key(), false),
+            AccountMeta::new(accounts.bonding_curve.key(), false),
+            AccountMeta::new(accounts.associated_bonding_curve.key(), false),
+            AccountMeta::new(accounts.associated_user.key(), false),
+            AccountMeta::new(accounts.user.key(), true),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+            AccountMeta::new(accounts.creator_vault.key(), false),
+            AccountMeta::new_readonly(accounts.event_authority.key(), false),
+            AccountMeta::new_readonly(accounts.program.key(), false),
+        ],
+        data,
+    };
+
+    invoke_signed(
+        &ix,
+        &[
+            accounts.global.clone(),
+            accounts.fee_recipient.clone(),
+            accounts.mint.clone(),
+            accounts.bonding_curve.clone(),
+            accounts.associated_bonding_curve.clone(),
+            accounts.associated_user.clone(),
+            accounts.user.clone(),
+            accounts.system_program.clone(),
+            accounts.token_program.clone(),
+            accounts.creator_vault.clone(),
+            accounts.event_authority.clone(),
+            accounts.program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
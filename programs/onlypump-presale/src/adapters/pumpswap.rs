@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use super::amm::{take_accounts, PoolDeposit};
+
+/// Mainnet PumpSwap AMM program (default for PlatformConfig::pumpswap_program)
+pub const PUMPSWAP_PROGRAM_ID: Pubkey = pubkey!("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA");
+
+/// Anchor discriminator: sha256("global:create_pool")[..8]
+const CREATE_POOL_DISCRIMINATOR: [u8; 8] = [233, 146, 209, 142, 207, 104, 64, 188];
+
+/// Pools created by the presale always use index 0 (one pool per creator/mint pair)
+const POOL_INDEX: u16 = 0;
+
+#[derive(AnchorSerialize)]
+struct CreatePoolArgs {
+    index: u16,
+    base_amount_in: u64,
+    quote_amount_in: u64,
+    coin_creator: Pubkey,
+}
+
+/// CPI into PumpSwap `create_pool`.
+/// `pool_accounts`: [pool, global_config, lp_mint, user_pool_token_account,
+/// pool_base_token_account, pool_quote_token_account, token_2022_program, event_authority]
+pub fn create_pool<'info>(
+    program: &AccountInfo<'info>,
+    deposit: &PoolDeposit<'_, 'info>,
+    pool_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<AccountInfo<'info>> {
+    let [pool, global_config, lp_mint, user_pool_token_account, pool_base_token_account, pool_quote_token_account, token_2022_program, event_authority] =
+        take_accounts::<8>(pool_accounts)?;
+
+    let mut data = CREATE_POOL_DISCRIMINATOR.to_vec();
+    CreatePoolArgs {
+        index: POOL_INDEX,
+        base_amount_in: deposit.base_amount,
+        quote_amount_in: deposit.quote_amount,
+        coin_creator: deposit.creator.key(),
+    }
+    .serialize(&mut data)?;
+
+    let account_infos = [
+        pool.clone(),
+        global_config.clone(),
+        deposit.creator.clone(),
+        deposit.base_mint.clone(),
+        deposit.quote_mint.clone(),
+        lp_mint.clone(),
+        deposit.creator_base_account.clone(),
+        deposit.creator_quote_account.clone(),
+        user_pool_token_account.clone(),
+        pool_base_token_account.clone(),
+        pool_quote_token_account.clone(),
+        deposit.system_program.clone(),
+        token_2022_program.clone(),
+        deposit.token_program.clone(),
+        deposit.token_program.clone(),
+        deposit.associated_token_program.clone(),
+        event_authority.clone(),
+        program.clone(),
+    ];
+    let ix = Instruction {
+        program_id: program.key(),
+        accounts: vec![
+            AccountMeta::new(pool.key(), false),
+            AccountMeta::new_readonly(global_config.key(), false),
+            AccountMeta::new(deposit.creator.key(), true),
+            AccountMeta::new_readonly(deposit.base_mint.key(), false),
+            AccountMeta::new_readonly(deposit.quote_mint.key(), false),
+            AccountMeta::new(lp_mint.key(), false),
+            AccountMeta::new(deposit.creator_base_account.key(), false),
+            AccountMeta::new(deposit.creator_quote_account.key(), false),
+            AccountMeta::new(user_pool_token_account.key(), false),
+            AccountMeta::new(pool_base_token_account.key(), false),
+            AccountMeta::new(pool_quote_token_account.key(), false),
+            AccountMeta::new_readonly(deposit.system_program.key(), false),
+            AccountMeta::new_readonly(token_2022_program.key(), false),
+            AccountMeta::new_readonly(deposit.token_program.key(), false),
+            AccountMeta::new_readonly(deposit.token_program.key(), false),
+            AccountMeta::new_readonly(deposit.associated_token_program.key(), false),
+            AccountMeta::new_readonly(event_authority.key(), false),
+            AccountMeta::new_readonly(program.key(), false),
+        ],
+        data,
+    };
+
+    invoke_signed(&ix, &account_infos, signer_seeds)?;
+    Ok(user_pool_token_account.clone())
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use super::amm::{take_accounts, PoolDeposit};
+
+/// Mainnet Raydium CPMM program (default for PlatformConfig::raydium_cpmm_program)
+pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+
+/// Anchor discriminator: sha256("global:initialize")[..8]
+const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+
+#[derive(AnchorSerialize)]
+struct InitializeArgs {
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+}
+
+/// CPI into Raydium CPMM `initialize`, trading immediately (open_time = 0).
+/// CPMM orders the pair by mint address, so base/quote are mapped onto token_0/token_1 here.
+/// `pool_accounts`: [amm_config, authority, pool_state, lp_mint, creator_lp_token,
+/// token_0_vault, token_1_vault, create_pool_fee, observation_state]
+pub fn initialize<'info>(
+    program: &AccountInfo<'info>,
+    deposit: &PoolDeposit<'_, 'info>,
+    pool_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<AccountInfo<'info>> {
+    let [amm_config, authority, pool_state, lp_mint, creator_lp_token, token_0_vault, token_1_vault, create_pool_fee, observation_state] =
+        take_accounts::<9>(pool_accounts)?;
+
+    let base_first = deposit.base_mint.key() < deposit.quote_mint.key();
+    let (mint_0, mint_1, creator_token_0, creator_token_1, amount_0, amount_1) = if base_first {
+        (
+            deposit.base_mint,
+            deposit.quote_mint,
+            deposit.creator_base_account,
+            deposit.creator_quote_account,
+            deposit.base_amount,
+            deposit.quote_amount,
+        )
+    } else {
+        (
+            deposit.quote_mint,
+            deposit.base_mint,
+            deposit.creator_quote_account,
+            deposit.creator_base_account,
+            deposit.quote_amount,
+            deposit.base_amount,
+        )
+    };
+
+    let mut data = INITIALIZE_DISCRIMINATOR.to_vec();
+    InitializeArgs {
+        init_amount_0: amount_0,
+        init_amount_1: amount_1,
+        open_time: 0,
+    }
+    .serialize(&mut data)?;
+
+    let account_infos = [
+        deposit.creator.clone(),
+        amm_config.clone(),
+        authority.clone(),
+        pool_state.clone(),
+        mint_0.clone(),
+        mint_1.clone(),
+        lp_mint.clone(),
+        creator_token_0.clone(),
+        creator_token_1.clone(),
+        creator_lp_token.clone(),
+        token_0_vault.clone(),
+        token_1_vault.clone(),
+        create_pool_fee.clone(),
+        observation_state.clone(),
+        deposit.token_program.clone(),
+        deposit.token_program.clone(),
+        deposit.token_program.clone(),
+        deposit.associated_token_program.clone(),
+        deposit.system_program.clone(),
+        deposit.rent.clone(),
+        program.clone(),
+    ];
+    let ix = Instruction {
+        program_id: program.key(),
+        accounts: vec![
+            AccountMeta::new(deposit.creator.key(), true),
+            AccountMeta::new_readonly(amm_config.key(), false),
+            AccountMeta::new_readonly(authority.key(), false),
+            AccountMeta::new(pool_state.key(), false),
+            AccountMeta::new_readonly(mint_0.key(), false),
+            AccountMeta::new_readonly(mint_1.key(), false),
+            AccountMeta::new(lp_mint.key(), false),
+            AccountMeta::new(creator_token_0.key(), false),
+            AccountMeta::new(creator_token_1.key(), false),
+            AccountMeta::new(creator_lp_token.key(), false),
+            AccountMeta::new(token_0_vault.key(), false),
+            AccountMeta::new(token_1_vault.key(), false),
+            AccountMeta::new(create_pool_fee.key(), false),
+            AccountMeta::new(observation_state.key(), false),
+            AccountMeta::new_readonly(deposit.token_program.key(), false),
+            AccountMeta::new_readonly(deposit.token_program.key(), false),
+            AccountMeta::new_readonly(deposit.token_program.key(), false),
+            AccountMeta::new_readonly(deposit.associated_token_program.key(), false),
+            AccountMeta::new_readonly(deposit.system_program.key(), false),
+            AccountMeta::new_readonly(deposit.rent.key(), false),
+        ],
+        data,
+    };
+
+    invoke_signed(&ix, &account_infos, signer_seeds)?;
+    Ok(creator_lp_token.clone())
+}
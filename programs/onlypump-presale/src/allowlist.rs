@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use solana_keccak_hasher as keccak;
+use solana_sdk_ids::ed25519_program;
+use crate::state::data::{
+    AllowlistPool, AllowlistProof, ContributionVoucher, PlatformConfig, Presale, WhitelistEntry,
+};
+use crate::errors::PresaleError;
+
+/// Whitelist terms for one wallet, regardless of where they came from
+/// (WhitelistEntry PDA, Merkle proof against a tier root or signed voucher)
+#[derive(Clone, Copy, Debug)]
+pub struct WhitelistGrant {
+    pub tier: u8,
+    /// 0 = no per-grant cap
+    pub max_contribution_lamports: u64,
+    /// Set for voucher grants; the caller must record it on the UserPosition
+    pub voucher_nonce: Option<u64>,
+}
+
+/// Resolve the caller's whitelist grant for `pool`.
+/// A proof passed as an instruction argument takes precedence over the WhitelistEntry account.
+/// Merkle leaves and vouchers are bound to a pool; a WhitelistEntry covers both.
+/// Voucher nonces are returned in the grant, replay is checked against the position by the caller.
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_whitelist_grant(
+    pool: AllowlistPool,
+    presale: &Account<Presale>,
+    platform: &PlatformConfig,
+    instructions_sysvar: &AccountInfo,
+    user: &Pubkey,
+    entry: Option<&WhitelistEntry>,
+    proof: Option<&AllowlistProof>,
+    now: i64,
+) -> Result<Option<WhitelistGrant>> {
+    match proof {
+        Some(AllowlistProof::Merkle {
+            tier,
+            max_contribution_lamports,
+            proof,
+        }) => {
+            let root = presale
+                .whitelist_root(*tier)
+                .ok_or(PresaleError::InvalidTier)?;
+            require!(*root != [0u8; 32], PresaleError::WhitelistRootNotSet);
+
+            let leaf = merkle_leaf(user, pool, *tier, *max_contribution_lamports);
+            require!(
+                verify_merkle_proof(leaf, proof, root),
+                PresaleError::InvalidMerkleProof
+            );
+
+            Ok(Some(WhitelistGrant {
+                tier: *tier,
+                max_contribution_lamports: *max_contribution_lamports,
+                voucher_nonce: None,
+            }))
+        }
+        Some(AllowlistProof::Voucher(voucher)) => {
+            require!(
+                platform.voucher_signer != Pubkey::default(),
+                PresaleError::VoucherSignerNotSet
+            );
+            require!(
+                voucher.presale == presale.key() && voucher.user == *user && voucher.pool == pool,
+                PresaleError::InvalidVoucher
+            );
+            require!(now <= voucher.expiry_ts, PresaleError::VoucherExpired);
+            verify_voucher_signature(instructions_sysvar, &platform.voucher_signer, voucher)?;
+
+            Ok(Some(WhitelistGrant {
+                tier: voucher.tier,
+                max_contribution_lamports: voucher.max_contribution_lamports,
+                voucher_nonce: Some(voucher.nonce),
+            }))
+        }
+        None => Ok(entry.map(|entry| WhitelistGrant {
+            tier: entry.tier,
+            max_contribution_lamports: entry.max_contribution_lamports,
+            voucher_nonce: None,
+        })),
+    }
+}
+
+/// Leaf = keccak256(user || pool (0 = Public, 1 = Vip) || tier || max_contribution_lamports as u64 LE)
+pub fn merkle_leaf(
+    user: &Pubkey,
+    pool: AllowlistPool,
+    tier: u8,
+    max_contribution_lamports: u64,
+) -> [u8; 32] {
+    keccak::hashv(&[
+        user.as_ref(),
+        &[pool as u8],
+        &[tier],
+        &max_contribution_lamports.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Verify a proof built with sorted pairs (parent = keccak256(min(a, b) || max(a, b)))
+pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == *root
+}
+
+/// Size of one signature offsets entry in Ed25519 program instruction data
+const ED25519_OFFSETS_LEN: usize = 14;
+/// Offsets start after num_signatures (u8) and padding (u8)
+const ED25519_OFFSETS_START: usize = 2;
+
+/// Check that the instruction right before this one is an Ed25519 sig-verify of
+/// `voucher` (Borsh-serialized) by `signer`.
+/// The runtime has already verified the signature itself; this only makes sure it
+/// covers the expected key and message, both stored inside the Ed25519 instruction.
+pub fn verify_voucher_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    voucher: &ContributionVoucher,
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, PresaleError::InvalidVoucherSignature);
+    let ix = load_instruction_at_checked(usize::from(current_index - 1), instructions_sysvar)?;
+    require!(
+        ix.program_id == ed25519_program::ID,
+        PresaleError::InvalidVoucherSignature
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_LEN && data[0] == 1,
+        PresaleError::InvalidVoucherSignature
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = ED25519_OFFSETS_START;
+    let signature_ix_index = read_u16(offsets + 2);
+    let public_key_offset = usize::from(read_u16(offsets + 4));
+    let public_key_ix_index = read_u16(offsets + 6);
+    let message_offset = usize::from(read_u16(offsets + 8));
+    let message_size = usize::from(read_u16(offsets + 10));
+    let message_ix_index = read_u16(offsets + 12);
+
+    // Key, signature and message must all live in the Ed25519 instruction itself
+    require!(
+        signature_ix_index == u16::MAX
+            && public_key_ix_index == u16::MAX
+            && message_ix_index == u16::MAX,
+        PresaleError::InvalidVoucherSignature
+    );
+
+    let mut message = Vec::new();
+    voucher.serialize(&mut message)?;
+
+    let public_key = data.get(public_key_offset..public_key_offset + 32);
+    let signed_message = data.get(message_offset..message_offset + message_size);
+    require!(
+        public_key == Some(signer.as_ref()) && signed_message == Some(message.as_slice()),
+        PresaleError::InvalidVoucherSignature
+    );
+
+    Ok(())
+}
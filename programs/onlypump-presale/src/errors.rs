@@ -26,5 +26,75 @@ pub enum PresaleError {
     NothingToClaim,
     #[msg("Insufficient funds")]
     InsufficientFunds,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Fee exceeds maximum")]
+    FeeTooHigh,
+    #[msg("No pending owner")]
+    NoPendingOwner,
+    #[msg("Invalid phase transition")]
+    InvalidPhaseTransition,
+    #[msg("Presale has not ended")]
+    PresaleNotEnded,
+    #[msg("Invalid presale config")]
+    InvalidPresaleConfig,
+    #[msg("Mint does not match presale")]
+    MintMismatch,
+    #[msg("Mint decimals do not match declared allocation")]
+    MintDecimalsMismatch,
+    #[msg("Mint supply is smaller than declared allocation")]
+    InsufficientMintSupply,
+    #[msg("Funding amount does not match declared allocation")]
+    FundingAmountMismatch,
+    #[msg("Contribution below minimum")]
+    ContributionBelowMinimum,
+    #[msg("Per-wallet contribution cap exceeded")]
+    WalletCapExceeded,
+    #[msg("Whitelist contribution cap exceeded")]
+    WhitelistCapExceeded,
+    #[msg("Whitelist tier contribution cap exceeded")]
+    TierCapExceeded,
+    #[msg("Invalid whitelist tier")]
+    InvalidTier,
+    #[msg("Whitelist root not set for tier")]
+    WhitelistRootNotSet,
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Contribution vouchers are not enabled")]
+    VoucherSignerNotSet,
+    #[msg("Voucher was issued for a different presale or wallet")]
+    InvalidVoucher,
+    #[msg("Voucher has expired")]
+    VoucherExpired,
+    #[msg("Missing or mismatched Ed25519 voucher signature")]
+    InvalidVoucherSignature,
+    #[msg("Voucher nonce already used")]
+    VoucherNonceReused,
+    #[msg("Token funding is already sealed")]
+    FundingSealed,
+    #[msg("Token funding is not sealed yet")]
+    FundingNotSealed,
+    #[msg("Not enough pool accounts for the AMM")]
+    MissingPoolAccounts,
+    #[msg("AMM did not mint LP tokens to lp_authority")]
+    LpNotMinted,
+    #[msg("LP tokens are still locked")]
+    LpStillLocked,
+    #[msg("Launch funds were already withdrawn")]
+    LaunchFundsAlreadyWithdrawn,
+    #[msg("Presale has no SOL escrow")]
+    EscrowNotEnabled,
+    #[msg("Escrow deadline has not passed")]
+    EscrowDeadlineNotPassed,
+    #[msg("Creator bond can only be returned after a sealed launch or a vote refund")]
+    CreatorBondNotReturnable,
+    #[msg("Position is not delegated to this voter")]
+    InvalidDelegation,
+    #[msg("Escrowed SOL must be released before the raise can be spent")]
+    EscrowPending,
+    #[msg("Voter has no voting weight")]
+    NoVotingWeight,
+    #[msg("Launch deadline has passed")]
+    LaunchDeadlinePassed,
 }
 
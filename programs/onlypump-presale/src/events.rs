@@ -1,27 +1,157 @@
 use anchor_lang::prelude::*;
+use crate::state::data::{AmmKind, PresalePhase};
 
 #[event]
 pub struct ContributePublicEvent {
     pub user: Pubkey,
     pub presale: Pubkey,
+    /// Lamports the user asked to contribute
+    pub requested_lamports: u64,
+    /// Lamports actually taken (less than requested on a partial fill)
     pub amount_lamports: u64,
     pub tokens_allocated: u64,
     pub total_raised: u64,
+    pub total_tokens_allocated: u64,
+    /// Whitelist tier the contribution was priced at (0 = public price)
+    pub tier: u8,
+    pub price_lamports_per_token: u64,
+}
+
+#[event]
+pub struct ContributeVipEvent {
+    pub user: Pubkey,
+    pub presale: Pubkey,
+    pub amount_lamports: u64,
+    pub tokens_allocated: u64,
+    pub total_vip_raised: u64,
+    pub total_tokens_allocated: u64,
+    pub tier: u8,
+}
+
+#[event]
+pub struct VipRewardsClaimed {
+    pub user: Pubkey,
+    pub presale: Pubkey,
+    pub amount_lamports: u64,
 }
 
 #[event]
 pub struct FinalizePresaleEvent {
     pub presale: Pubkey,
     pub total_raised: u64,
+    pub soft_cap_met: bool,
+}
+
+#[event]
+pub struct FundingSealed {
+    pub presale: Pubkey,
+    pub public_tokens: u64,
+    pub lp_tokens: u64,
+    pub ecosystem_tokens: u64,
+}
+
+#[event]
+pub struct PumpfunLaunched {
+    pub presale: Pubkey,
+    pub mint: Pubkey,
+    pub sol_spent: u64,
+    pub tokens_bought: u64,
+    pub platform_fee: u64,
 }
 
 #[event]
 pub struct MigrateAndCreateLpEvent {
     pub presale: Pubkey,
+    pub amm: AmmKind,
+    pub lp_mint: Pubkey,
+    /// LP tokens minted to lp_authority
+    pub lp_minted: u64,
     pub lp_tokens: u64,
     pub lp_sol: u64,
     pub ecosystem_tokens: u64,
     pub remaining_sol_to_treasury: u64,
+    pub platform_fee: u64,
+}
+
+/// SOL paid to the creator by withdraw_for_launch / release_escrow
+#[event]
+pub struct EscrowReleased {
+    pub presale: Pubkey,
+    pub authority: Pubkey,
+    pub amount_lamports: u64,
+    pub platform_fee: u64,
+    /// Cumulative share of the raise released so far
+    pub released_bps: u16,
+}
+
+#[event]
+pub struct VoteDelegated {
+    pub presale: Pubkey,
+    pub user: Pubkey,
+    /// Pubkey::default() when the delegation was removed
+    pub delegate: Pubkey,
+}
+
+/// Final tallies and thresholds of a resolved vote
+#[event]
+pub struct VoteResolved {
+    pub presale: Pubkey,
+    pub yes_weight: u64,
+    pub no_weight: u64,
+    /// Contribution lamports that voted (unweighted), measured against the quorum
+    pub turnout_lamports: u64,
+    pub total_raised_lamports: u64,
+    pub quorum_bps: u16,
+    pub approval_bps: u16,
+    pub quorum_reached: bool,
+    /// vote::outcome::LAUNCH or vote::outcome::REFUND
+    pub outcome: u8,
+}
+
+/// Creator bond handed to contributors after a missed launch / escrow deadline
+#[event]
+pub struct CreatorBondSlashed {
+    pub presale: Pubkey,
+    pub amount_lamports: u64,
+}
+
+#[event]
+pub struct CreatorBondReturned {
+    pub presale: Pubkey,
+    pub authority: Pubkey,
+    pub amount_lamports: u64,
+}
+
+#[event]
+pub struct LpLocked {
+    pub presale: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_vault: Pubkey,
+    pub amount: u64,
+    pub unlock_ts: i64,
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct LpUnlocked {
+    pub presale: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LpBurned {
+    pub presale: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RevenueShared {
+    pub presale: Pubkey,
+    pub creator: Pubkey,
+    pub creator_lamports: u64,
+    pub platform_lamports: u64,
+    pub vip_rewards_lamports: u64,
 }
 
 #[event]
@@ -31,3 +161,54 @@ pub struct ClaimTokensEvent {
     pub tokens_claimed: u64,
 }
 
+#[event]
+pub struct FeeCollected {
+    pub presale: Pubkey,
+    pub gross_lamports: u64,
+    pub fee_lamports: u64,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct FeesSwept {
+    pub treasury: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PlatformConfigUpdated {
+    pub operator: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+    pub voucher_signer: Pubkey,
+    pub pumpfun_program: Pubkey,
+    pub pumpswap_program: Pubkey,
+    pub raydium_cpmm_program: Pubkey,
+}
+
+#[event]
+pub struct OwnershipTransferProposed {
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnershipTransferred {
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct PhaseChanged {
+    pub presale: Pubkey,
+    pub from: PresalePhase,
+    pub to: PresalePhase,
+}
+
+#[event]
+pub struct WhitelistRootSet {
+    pub presale: Pubkey,
+    pub tier: u8,
+    pub root: [u8; 32],
+}
+
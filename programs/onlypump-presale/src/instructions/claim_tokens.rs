@@ -4,17 +4,22 @@ use crate::state::accounts::ClaimTokens;
 use crate::errors::PresaleError;
 use crate::events::ClaimTokensEvent;
 
-/// User claims their allocated tokens after migration
-/// Preconditions: presale.is_migrated == true
-/// Transfers tokens from token_vault to user's ATA
+/// User claims their vested tokens after migration
+/// Preconditions: presale.funding_sealed == true, presale.is_migrated == true
+/// Transfers the vested but unclaimed part of the position's entitlement (tokens_allocated,
+/// or a pro-rata share of funded_public_tokens, see Presale::claim_mode) to user's ATA;
+/// can be called repeatedly as more tokens vest (see Presale::vesting)
 pub fn claim_tokens(ctx: Context<ClaimTokens>) -> Result<()> {
     let presale = &ctx.accounts.presale;
 
+    require!(presale.funding_sealed, PresaleError::FundingNotSealed);
     require!(presale.is_migrated, PresaleError::PresaleNotMigrated);
 
     let position = &mut ctx.accounts.user_position;
-    let claimable = position
-        .tokens_allocated
+    let entitlement =
+        presale.token_entitlement(position.public_contribution_lamports, position.tokens_allocated)?;
+    let vested = presale.vested_amount(entitlement, Clock::get()?.unix_timestamp)?;
+    let claimable = vested
         .checked_sub(position.tokens_claimed)
         .ok_or(PresaleError::NothingToClaim)?;
 
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::state::accounts::ConfigureInfluencer;
+use crate::errors::PresaleError;
+use crate::utils::BPS_DENOMINATOR;
+
+/// Set (or update) the revenue split applied to the migration payout
+/// Admin-only, before migration
+/// PDA seeds: ["influencer", presale_pubkey]
+pub fn configure_influencer(
+    ctx: Context<ConfigureInfluencer>,
+    creator: Pubkey,
+    creator_share_bps: u16,
+    vip_share_from_creator_bps: u16,
+    vip_share_from_platform_bps: u16,
+) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    require!(
+        !ctx.accounts.presale.is_migrated,
+        PresaleError::PresaleAlreadyMigrated
+    );
+    require!(
+        [
+            creator_share_bps,
+            vip_share_from_creator_bps,
+            vip_share_from_platform_bps,
+        ]
+        .iter()
+        .all(|bps| u64::from(*bps) <= BPS_DENOMINATOR),
+        PresaleError::InvalidPresaleConfig
+    );
+
+    let config = &mut ctx.accounts.influencer_config;
+    config.creator = creator;
+    config.presale = ctx.accounts.presale.key();
+    config.creator_share_bps = creator_share_bps;
+    config.vip_share_from_creator_bps = vip_share_from_creator_bps;
+    config.vip_share_from_platform_bps = vip_share_from_platform_bps;
+    config.bump = ctx.bumps.influencer_config;
+    ctx.accounts.presale.has_influencer_config = true;
+
+    Ok(())
+}
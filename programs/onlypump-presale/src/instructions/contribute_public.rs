@@ -2,81 +2,168 @@ use anchor_lang::prelude::*;
 use crate::state::accounts::ContributePublic;
 use crate::errors::PresaleError;
 use crate::events::ContributePublicEvent;
+use crate::state::data::{AllowlistPool, AllowlistProof, ContributionWindow, PresalePhase};
+use crate::allowlist::resolve_whitelist_grant;
+use crate::utils::apply_bps;
 
 /// User contributes SOL to the public presale
 /// Transfers SOL to public_sol_vault and tracks allocation in UserPosition
+/// During [whitelist_start_ts, whitelist_end_ts) only whitelisted wallets (tier >= 1) may
+/// contribute, at their tier's discounted price and within their tier's cap; the public
+/// window [public_start_ts, public_end_ts) is open to everyone at the public price.
+/// Whitelist membership comes from the WhitelistEntry PDA, a Merkle proof against the
+/// tier root set with set_whitelist_root, or a backend-signed voucher verified through a
+/// preceding Ed25519 instruction. Voucher nonces must strictly increase per wallet.
+/// min_contribution_lamports applies to the lamports accepted by each call; the per-wallet,
+/// whitelist and tier caps apply to the wallet's cumulative contribution including them.
+/// The hard cap and the global public_token_cap are enforced across all positions.
+/// With `allow_partial_fill`, a contribution that would overshoot either cap is cut
+/// down to the lamports that still fit; the remainder never leaves the user's wallet.
 /// PDA seeds for UserPosition: ["position", presale_pubkey, user_pubkey]
-pub fn contribute_public(ctx: Context<ContributePublic>, amount_lamports: u64) -> Result<()> {
+pub fn contribute_public(
+    ctx: Context<ContributePublic>,
+    amount_lamports: u64,
+    allow_partial_fill: bool,
+    allowlist_proof: Option<AllowlistProof>,
+) -> Result<()> {
     let presale = &ctx.accounts.presale;
     let presale_key = presale.key(); // Store key before mutable borrow
 
-    // NOTE: In production you likely want to enforce the presale time window:
-    // let clock = Clock::get()?;
-    // require!(
-    //     clock.unix_timestamp >= presale.public_start_ts &&
-    //     clock.unix_timestamp < presale.public_end_ts,
-    //     PresaleError::PresaleNotActive
-    // );
-    //
-    // For local testing and flexibility we skip the time check here and only
-    // require that the presale has not already been finalized.
     require!(!presale.is_finalized, PresaleError::PresaleAlreadyFinalized);
+    require!(
+        presale.phase == PresalePhase::PublicActive,
+        PresaleError::PresaleNotActive
+    );
 
-    // Check hard cap
+    // Whitelist window: whitelisted wallets only, at their tier's price and cap.
+    // Public window: everyone. With time checks disabled the public window is always open.
+    let now = Clock::get()?.unix_timestamp;
+    let window = presale.contribution_window(now);
     require!(
+        window != ContributionWindow::Closed,
+        PresaleError::PresaleNotActive
+    );
+
+    // Whitelist grant from a Merkle proof / voucher argument or the WhitelistEntry PDA, if provided
+    let grant = resolve_whitelist_grant(
+        AllowlistPool::Public,
+        presale,
+        &ctx.accounts.platform,
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.user.key(),
+        ctx.accounts.whitelist.as_deref(),
+        allowlist_proof.as_ref(),
+        now,
+    )?
+    // Tier 0 means not whitelisted: such a grant is ignored (and not enough for the whitelist window)
+    .filter(|grant| grant.tier >= 1);
+    if let Some(grant) = &grant {
+        if let Some(nonce) = grant.voucher_nonce {
+            require!(
+                nonce > ctx.accounts.user_position.voucher_nonce,
+                PresaleError::VoucherNonceReused
+            );
+        }
+    }
+
+    // Tier terms only apply in the whitelist window; everyone pays the public price after
+    let mut price = presale.public_price_lamports_per_token;
+    let mut tier = 0;
+    let mut tier_cap_lamports = 0;
+    if window == ContributionWindow::Whitelist {
+        let grant = grant.ok_or(PresaleError::NotWhitelisted)?;
+        let tier_config = presale
+            .tier_config(grant.tier)
+            .ok_or(PresaleError::NotWhitelisted)?;
+        tier_cap_lamports = tier_config.max_contribution_lamports;
+        price = price
+            .checked_sub(apply_bps(price, tier_config.discount_bps)?)
+            .ok_or(PresaleError::MathOverflow)?;
+        require!(price > 0, PresaleError::InvalidPresaleConfig);
+        tier = grant.tier;
+    }
+
+    // Work out how many lamports still fit under the hard cap and the global token cap
+    let hard_cap_room = presale
+        .hard_cap_lamports
+        .saturating_sub(presale.public_raised_lamports);
+    let token_cap_room = presale.lamports_for_tokens(
         presale
-            .public_raised_lamports
-            .checked_add(amount_lamports)
-            .ok_or(PresaleError::HardCapExceeded)?
-            <= presale.hard_cap_lamports,
-        PresaleError::HardCapExceeded
+            .public_token_cap
+            .saturating_sub(presale.total_tokens_allocated),
+        price,
+    )?;
+    let fillable_lamports = hard_cap_room.min(token_cap_room);
+
+    let accepted_lamports = if amount_lamports <= fillable_lamports {
+        amount_lamports
+    } else {
+        require!(
+            allow_partial_fill && fillable_lamports > 0,
+            if hard_cap_room <= token_cap_room {
+                PresaleError::HardCapExceeded
+            } else {
+                PresaleError::TokenCapExceeded
+            }
+        );
+        fillable_lamports
+    };
+    // A partial fill must still meet the minimum
+    require!(
+        accepted_lamports >= presale.min_contribution_lamports,
+        PresaleError::ContributionBelowMinimum
     );
 
-    // Check whitelist if provided
-    if let Some(whitelist) = &ctx.accounts.whitelist {
-        require!(whitelist.tier >= 1, PresaleError::NotWhitelisted);
+    // Wallet, whitelist and tier caps apply to everything this wallet has contributed so far,
+    // counting only the lamports this call accepts
+    let wallet_total = ctx
+        .accounts
+        .user_position
+        .public_contribution_lamports
+        .checked_add(accepted_lamports)
+        .ok_or(PresaleError::MathOverflow)?;
+    require!(
+        presale.max_contribution_per_wallet_lamports == 0
+            || wallet_total <= presale.max_contribution_per_wallet_lamports,
+        PresaleError::WalletCapExceeded
+    );
+    if let Some(grant) = &grant {
         require!(
-            whitelist.max_contribution_lamports == 0
-                || amount_lamports <= whitelist.max_contribution_lamports,
-            PresaleError::ContributionTooLarge
+            grant.max_contribution_lamports == 0
+                || wallet_total <= grant.max_contribution_lamports,
+            PresaleError::WhitelistCapExceeded
         );
     }
+    require!(
+        tier_cap_lamports == 0 || wallet_total <= tier_cap_lamports,
+        PresaleError::TierCapExceeded
+    );
 
     // Calculate token allocation
-    // tokens = (amount_lamports * TOKEN_PRECISION) / public_price_lamports_per_token
-    // Using checked math to prevent overflow
-    // Assumption: We use 1e9 as TOKEN_PRECISION for calculations (matching 9 decimals for SOL precision)
-    const TOKEN_PRECISION: u64 = 1_000_000_000;
-    let tokens_to_allocate = amount_lamports
-        .checked_mul(TOKEN_PRECISION)
-        .ok_or(PresaleError::TokenCapExceeded)?
-        .checked_div(presale.public_price_lamports_per_token)
-        .ok_or(PresaleError::TokenCapExceeded)?;
-
-    // Check total token cap (400M)
-    // Note: In production, you'd track total_allocated_tokens in Presale account
-    // For MVP, we rely on the hard_cap_lamports to limit total contributions
-    // and trust that the price calculation won't exceed public_token_cap
-    let position = &mut ctx.accounts.user_position;
-    let current_allocated = position.tokens_allocated;
-    let new_total_tokens = current_allocated
-        .checked_add(tokens_to_allocate)
-        .ok_or(PresaleError::TokenCapExceeded)?;
+    // tokens = accepted_lamports * 10^token_decimals / price (tier-discounted in the whitelist window)
+    let tokens_to_allocate = presale.tokens_for_lamports(accepted_lamports, price)?;
 
-    // Basic sanity check - in production, sum all UserPositions
-    // For now, we ensure individual positions don't exceed the cap
-    // The hard_cap_lamports should be set such that total tokens <= public_token_cap
+    let new_total_allocated = presale
+        .total_tokens_allocated
+        .checked_add(tokens_to_allocate)
+        .ok_or(PresaleError::MathOverflow)?;
     require!(
-        new_total_tokens <= presale.public_token_cap,
+        new_total_allocated <= presale.public_token_cap,
         PresaleError::TokenCapExceeded
     );
 
-    // Transfer SOL from user to public_sol_vault
+    let position = &mut ctx.accounts.user_position;
+    let new_position_tokens = position
+        .tokens_allocated
+        .checked_add(tokens_to_allocate)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    // Transfer SOL from user to public_sol_vault (only the accepted part)
     anchor_lang::solana_program::program::invoke(
         &anchor_lang::solana_program::system_instruction::transfer(
             &ctx.accounts.user.key(),
             &ctx.accounts.public_sol_vault.key(),
-            amount_lamports,
+            accepted_lamports,
         ),
         &[
             ctx.accounts.user.to_account_info(),
@@ -88,28 +175,35 @@ pub fn contribute_public(ctx: Context<ContributePublic>, amount_lamports: u64) -
     let presale_mut = &mut ctx.accounts.presale;
     presale_mut.public_raised_lamports = presale_mut
         .public_raised_lamports
-        .checked_add(amount_lamports)
+        .checked_add(accepted_lamports)
         .ok_or(PresaleError::HardCapExceeded)?;
+    presale_mut.total_tokens_allocated = new_total_allocated;
 
     // Update user position
     position.presale = presale_key; // Use stored key
     position.user = ctx.accounts.user.key();
     position.public_contribution_lamports = position
         .public_contribution_lamports
-        .checked_add(amount_lamports)
+        .checked_add(accepted_lamports)
         .ok_or(PresaleError::HardCapExceeded)?;
-    position.tokens_allocated = new_total_tokens;
+    position.tokens_allocated = new_position_tokens;
     position.refunded = false;
+    if let Some(nonce) = grant.and_then(|grant| grant.voucher_nonce) {
+        position.voucher_nonce = nonce;
+    }
     position.bump = ctx.bumps.user_position;
 
     emit!(ContributePublicEvent {
         user: ctx.accounts.user.key(),
         presale: presale_key, // Use stored key
-        amount_lamports,
+        requested_lamports: amount_lamports,
+        amount_lamports: accepted_lamports,
         tokens_allocated: tokens_to_allocate,
         total_raised: presale_mut.public_raised_lamports,
+        total_tokens_allocated: presale_mut.total_tokens_allocated,
+        tier,
+        price_lamports_per_token: price,
     });
 
     Ok(())
 }
-
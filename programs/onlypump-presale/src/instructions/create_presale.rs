@@ -1,44 +1,109 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use crate::state::accounts::CreatePresale;
+use crate::state::data::{CreatePresaleParams, PresalePhase, TokenAllocation};
+use crate::errors::PresaleError;
+use crate::utils::BPS_DENOMINATOR;
 
 /// Create a new presale for a token (token doesn't need to exist yet)
 /// Admin-only (owner or operator)
-/// Creates Presale PDA and public_sol_vault (SOL vault only)
+/// Creates Presale PDA, public_sol_vault (SOL vault only) and creator_bond
+/// A non-zero params.creator_bond_lamports is paid by `creator`, who must be the authority
 /// Token vaults will be created later via initialize_vaults after token exists
 /// PDA seeds: ["presale", mint_pubkey]
 pub fn create_presale(
     ctx: Context<CreatePresale>,
     mint: Pubkey,
     authority: Pubkey,
-    public_start_ts: i64,
-    public_end_ts: i64,
-    public_price_lamports_per_token: u64,
-    hard_cap_lamports: u64,
+    params: CreatePresaleParams,
 ) -> Result<()> {
     ctx.accounts.validate()?;
 
+    require!(
+        params.public_start_ts < params.public_end_ts,
+        PresaleError::InvalidPresaleConfig
+    );
+    require!(
+        params.public_price_lamports_per_token > 0,
+        PresaleError::InvalidPresaleConfig
+    );
+    require!(
+        params.soft_cap_lamports <= params.hard_cap_lamports,
+        PresaleError::InvalidPresaleConfig
+    );
+    require!(
+        params.max_contribution_per_wallet_lamports == 0
+            || params.min_contribution_lamports <= params.max_contribution_per_wallet_lamports,
+        PresaleError::InvalidPresaleConfig
+    );
+    // Whitelist window is optional but, when set, must close before the public window opens
+    let has_whitelist_window = params.whitelist_start_ts != 0 || params.whitelist_end_ts != 0;
+    require!(
+        !has_whitelist_window
+            || (params.whitelist_start_ts < params.whitelist_end_ts
+                && params.whitelist_end_ts <= params.public_start_ts),
+        PresaleError::InvalidPresaleConfig
+    );
+    require!(
+        params
+            .tiers
+            .iter()
+            .all(|tier| (tier.discount_bps as u64) < BPS_DENOMINATOR),
+        PresaleError::InvalidPresaleConfig
+    );
+    require!(params.vesting.is_valid(), PresaleError::InvalidPresaleConfig);
+    require!(params.lp_lock.lock_seconds >= 0, PresaleError::InvalidPresaleConfig);
+    require!(params.escrow.is_valid(), PresaleError::InvalidPresaleConfig);
+    require!(params.vote_config.is_valid(), PresaleError::InvalidPresaleConfig);
+    let allocation = &params.allocation;
+    require!(allocation.public_tokens > 0, PresaleError::InvalidPresaleConfig);
+    require!(
+        allocation.decimals <= TokenAllocation::MAX_DECIMALS,
+        PresaleError::InvalidPresaleConfig
+    );
+
+    let presale_key = ctx.accounts.presale.key();
     let presale = &mut ctx.accounts.presale;
     presale.platform = ctx.accounts.platform.key();
     presale.authority = authority;
     presale.mint = mint;
-    presale.public_start_ts = public_start_ts;
-    presale.public_end_ts = public_end_ts;
+    presale.public_start_ts = params.public_start_ts;
+    presale.public_end_ts = params.public_end_ts;
+    presale.whitelist_start_ts = params.whitelist_start_ts;
+    presale.whitelist_end_ts = params.whitelist_end_ts;
+    presale.tier_configs = params.tiers;
     // For now, default TGE to public_end_ts; backend can adjust via future admin instruction.
-    presale.tge_ts = public_end_ts;
-    presale.public_token_cap = 400_000_000_000_000; // 400M tokens (assuming 6 decimals)
-    presale.lp_token_allocation = 300_000_000_000_000; // 300M tokens
-    presale.ecosystem_allocation = 100_000_000_000_000; // 100M tokens
-    presale.public_price_lamports_per_token = public_price_lamports_per_token;
-    presale.hard_cap_lamports = hard_cap_lamports;
+    presale.tge_ts = params.public_end_ts;
+    presale.vesting = params.vesting;
+    presale.claim_mode = params.claim_mode;
+    presale.funded_tokens = 0;
+    presale.funding_sealed = false;
+    presale.funded_public_tokens = 0;
+    presale.funded_lp_tokens = 0;
+    presale.funded_ecosystem_tokens = 0;
+    presale.time_checks_disabled = params.time_checks_disabled;
+    // Decimals/supply are checked against the real mint once it exists (initialize_vaults)
+    presale.public_token_cap = allocation.public_tokens;
+    presale.lp_token_allocation = allocation.lp_tokens;
+    presale.ecosystem_allocation = allocation.ecosystem_tokens;
+    presale.token_decimals = allocation.decimals;
+    presale.total_token_allocation()?;
+    presale.public_price_lamports_per_token = params.public_price_lamports_per_token;
+    presale.hard_cap_lamports = params.hard_cap_lamports;
+    presale.soft_cap_lamports = params.soft_cap_lamports;
+    presale.min_contribution_lamports = params.min_contribution_lamports;
+    presale.max_contribution_per_wallet_lamports = params.max_contribution_per_wallet_lamports;
     presale.public_raised_lamports = 0;
+    presale.total_tokens_allocated = 0;
     presale.vip_raised_lamports = 0;
     presale.is_finalized = false;
     presale.is_migrated = false;
-    // Start directly in public active phase; VIP/voting handled off-chain/admin-triggered.
-    presale.phase = crate::instructions::vote::phase::PUBLIC_ACTIVE;
     presale.vote_yes_weight = 0;
     presale.vote_no_weight = 0;
     presale.voting_ends_ts = 0;
+    presale.vote_round = 0;
+    presale.vote_turnout_lamports = 0;
+    presale.has_influencer_config = false;
     presale.refund_enabled = false;
     presale.launch_deadline_ts = 0;
     presale.outcome = crate::instructions::vote::outcome::UNDECIDED;
@@ -49,11 +114,47 @@ pub fn create_presale(
     
     // Derive lp_authority PDA (will be same after initialize_vaults)
     let (lp_authority, _) = Pubkey::find_program_address(
-        &[b"lp_authority", presale.key().as_ref()],
+        &[b"lp_authority", presale_key.as_ref()],
         ctx.program_id,
     );
     presale.lp_authority = lp_authority;
+    presale.lp_lock = params.lp_lock;
+    if presale.lp_lock.recipient == Pubkey::default() {
+        presale.lp_lock.recipient = authority;
+    }
+    presale.lp_mint = Pubkey::default();
+    presale.lp_vault = Pubkey::default();
+    presale.lp_locked_amount = 0;
+    presale.lp_unlock_ts = 0;
+    presale.escrow = params.escrow;
+    presale.escrow_released_bps = 0;
+    presale.escrow_deadline_ts = 0;
+    presale.creator_bond_lamports = params.creator_bond_lamports;
+    presale.creator_bond_slashed = false;
+    presale.vote_config = params.vote_config;
+
+    if params.creator_bond_lamports > 0 {
+        let creator = ctx
+            .accounts
+            .creator
+            .as_ref()
+            .ok_or(PresaleError::Unauthorized)?;
+        require_keys_eq!(creator.key(), authority, PresaleError::Unauthorized);
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: creator.to_account_info(),
+                    to: ctx.accounts.creator_bond.to_account_info(),
+                },
+            ),
+            params.creator_bond_lamports,
+        )?;
+    }
+
+    // Open directly for public contributions; VIP/voting handled off-chain/admin-triggered.
+    presale.phase = PresalePhase::Pending;
+    presale.transition_to(presale_key, PresalePhase::PublicActive)?;
 
     Ok(())
 }
-
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::accounts::ReturnCreatorBond;
+use crate::state::data::PresalePhase;
+use crate::errors::PresaleError;
+use crate::events::CreatorBondReturned;
+use crate::utils::transfer_lamports;
+
+/// Return the creator bond to the presale authority
+/// Permissionless. Allowed once the launch went through (phase Launched with funding sealed)
+/// or when refunds were opened without the creator being at fault (vote / soft cap refund);
+/// a slashed bond stays in creator_bond for contributors' refunds.
+pub fn return_creator_bond(ctx: Context<ReturnCreatorBond>) -> Result<()> {
+    let presale = &mut ctx.accounts.presale;
+
+    require!(presale.creator_bond_lamports > 0, PresaleError::NothingToClaim);
+    require!(!presale.creator_bond_slashed, PresaleError::CreatorBondNotReturnable);
+    let launched = presale.phase == PresalePhase::Launched && presale.funding_sealed;
+    require!(
+        launched || presale.phase == PresalePhase::Refundable,
+        PresaleError::CreatorBondNotReturnable
+    );
+
+    let amount = presale.creator_bond_lamports;
+    transfer_lamports(
+        &ctx.accounts.creator_bond.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        amount,
+    )?;
+    presale.creator_bond_lamports = 0;
+
+    emit!(CreatorBondReturned {
+        presale: presale.key(),
+        authority: presale.authority,
+        amount_lamports: amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use crate::state::accounts::{EnableEscrowRefunds, ReleaseEscrow};
+use crate::state::data::PresalePhase;
+use crate::errors::PresaleError;
+use crate::events::EscrowReleased;
+use crate::instructions::vote::outcome;
+use crate::utils::{collect_fee, drain_vault, transfer_lamports, BPS_DENOMINATOR};
+
+/// Release the escrowed remainder of the raise to the creator
+/// Authority-only (presale.authority)
+/// Gated on proof of vault funding: the token vault must be funded and sealed
+/// (seal_funding), and escrow refunds must not have been opened.
+pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let presale = &ctx.accounts.presale;
+    require!(presale.escrow.is_enabled(), PresaleError::EscrowNotEnabled);
+    require!(presale.escrow_pending(), PresaleError::NothingToClaim);
+    require!(presale.funding_sealed, PresaleError::FundingNotSealed);
+    require!(!presale.refund_enabled, PresaleError::Unauthorized);
+
+    let public_sol_vault = ctx.accounts.public_sol_vault.to_account_info();
+    drain_vault(&ctx.accounts.vip_sol_vault.to_account_info(), &public_sol_vault)?;
+
+    let gross = public_sol_vault.lamports();
+    require!(gross > 0, PresaleError::InsufficientFunds);
+
+    let presale_key = presale.key();
+    let fee = collect_fee(
+        presale_key,
+        &public_sol_vault,
+        &ctx.accounts.fee_vault.to_account_info(),
+        gross,
+        ctx.accounts.platform.fee_bps,
+    )?;
+    let payout = gross.checked_sub(fee).ok_or(PresaleError::MathOverflow)?;
+    transfer_lamports(
+        &public_sol_vault,
+        &ctx.accounts.authority.to_account_info(),
+        payout,
+    )?;
+
+    let presale = &mut ctx.accounts.presale;
+    presale.escrow_released_bps = BPS_DENOMINATOR as u16;
+
+    emit!(EscrowReleased {
+        presale: presale_key,
+        authority: presale.authority,
+        amount_lamports: payout,
+        platform_fee: fee,
+        released_bps: presale.escrow_released_bps,
+    });
+
+    Ok(())
+}
+
+/// Open refunds of the escrowed remainder when the creator took the launch tranche but
+/// did not fund and seal the token vault before escrow_deadline_ts.
+/// Permissionless. Refunds then pay each contributor their unreleased share
+/// (see Presale::refund_amount) plus their share of the slashed creator bond.
+pub fn enable_escrow_refunds(ctx: Context<EnableEscrowRefunds>) -> Result<()> {
+    let presale_key = ctx.accounts.presale.key();
+    let presale = &mut ctx.accounts.presale;
+
+    require!(presale.escrow.is_enabled(), PresaleError::EscrowNotEnabled);
+    require!(presale.escrow_pending(), PresaleError::NothingToClaim);
+    require!(!presale.funding_sealed, PresaleError::FundingSealed);
+    require!(
+        Clock::get()?.unix_timestamp > presale.escrow_deadline_ts,
+        PresaleError::EscrowDeadlineNotPassed
+    );
+
+    presale.transition_to(presale_key, PresalePhase::Refundable)?;
+    presale.refund_enabled = true;
+    presale.outcome = outcome::REFUND;
+    presale.slash_creator_bond(presale_key);
+
+    Ok(())
+}
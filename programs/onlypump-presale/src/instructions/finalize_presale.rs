@@ -1,37 +1,39 @@
 use anchor_lang::prelude::*;
 use crate::state::accounts::FinalizePresale;
+use crate::state::data::PresalePhase;
 use crate::errors::PresaleError;
 use crate::events::FinalizePresaleEvent;
+use crate::instructions::vote::outcome;
 
 /// Finalize the presale
-/// Admin-only
-/// Can only be called after public_end_ts
+/// Permissionless after public_end_ts (admin-only before that, and only when time checks are disabled)
 /// Sets is_finalized = true
+/// If the raise (public + VIP) missed soft_cap_lamports, the presale skips the vote and goes
+/// straight to Refundable with refunds enabled
 pub fn finalize_presale(ctx: Context<FinalizePresale>) -> Result<()> {
-    ctx.accounts.validate()?;
+    let clock = Clock::get()?;
+    ctx.accounts.validate(clock.unix_timestamp)?;
 
+    let presale_key = ctx.accounts.presale.key();
     let presale = &mut ctx.accounts.presale;
 
-    // NOTE: In a production deployment, you likely want to enforce that the
-    // current time is past `public_end_ts` before allowing finalization, e.g.:
-    //
-    // let clock = Clock::get()?;
-    // require!(
-    //     clock.unix_timestamp >= presale.public_end_ts,
-    //     PresaleError::PresaleNotFinalized
-    // );
-    //
-    // For local testing and flexibility, we skip the time check here and rely
-    // on the admin to call this at the appropriate time.
     require!(!presale.is_finalized, PresaleError::PresaleAlreadyFinalized);
 
     presale.is_finalized = true;
 
+    let total_raised = presale.total_raised_lamports()?;
+    let soft_cap_met = total_raised >= presale.soft_cap_lamports;
+    if !soft_cap_met {
+        presale.transition_to(presale_key, PresalePhase::Refundable)?;
+        presale.outcome = outcome::REFUND;
+        presale.refund_enabled = true;
+    }
+
     emit!(FinalizePresaleEvent {
-        presale: presale.key(),
-        total_raised: presale.public_raised_lamports,
+        presale: presale_key,
+        total_raised,
+        soft_cap_met,
     });
 
     Ok(())
 }
-
@@ -1,14 +1,18 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Transfer};
 use crate::state::accounts::FundPresaleTokens;
+use crate::errors::PresaleError;
 
-/// Fund the presale token vault with the required tokens (800M total)
-/// Admin-only
-/// Transfers tokens from authority's token account to token_vault PDA
-/// Assumption: Exactly 800M tokens (400M + 300M + 100M) should be transferred
+/// Deposit tokens into the presale token vault
+/// Admin-only, until seal_funding
+/// Transfers tokens from authority's token account to token_vault PDA and adds them to
+/// presale.funded_tokens; the public/LP/ecosystem split is fixed later by seal_funding
 pub fn fund_presale_tokens(ctx: Context<FundPresaleTokens>, amount: u64) -> Result<()> {
     ctx.accounts.validate()?;
 
+    require!(!ctx.accounts.presale.funding_sealed, PresaleError::FundingSealed);
+    require!(amount > 0, PresaleError::FundingAmountMismatch);
+
     // Transfer tokens from authority to token_vault
     // Note: The authority must be the owner of from_token_account
     token::transfer(
@@ -23,6 +27,11 @@ pub fn fund_presale_tokens(ctx: Context<FundPresaleTokens>, amount: u64) -> Resu
         amount,
     )?;
 
+    let presale = &mut ctx.accounts.presale;
+    presale.funded_tokens = presale
+        .funded_tokens
+        .checked_add(amount)
+        .ok_or(PresaleError::MathOverflow)?;
+
     Ok(())
 }
-
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+use crate::state::accounts::GetVestedAmount;
+
+/// Read-only: tokens of a position vested at `timestamp` under the presale's vesting schedule
+/// (claimed or not). Meant for clients via simulation / `.view()`.
+/// In ProRata mode the entitlement is only known once seal_funding records funded_public_tokens.
+pub fn get_vested_amount(ctx: Context<GetVestedAmount>, timestamp: i64) -> Result<u64> {
+    let presale = &ctx.accounts.presale;
+    let position = &ctx.accounts.user_position;
+    let entitlement = presale
+        .token_entitlement(position.public_contribution_lamports, position.tokens_allocated)?;
+    presale.vested_amount(entitlement, timestamp)
+}
@@ -1,5 +1,10 @@
 use anchor_lang::prelude::*;
 use crate::state::accounts::InitializePlatform;
+use crate::state::data::PlatformConfig;
+use crate::errors::PresaleError;
+use crate::adapters::pumpfun::PUMPFUN_PROGRAM_ID;
+use crate::adapters::pumpswap::PUMPSWAP_PROGRAM_ID;
+use crate::adapters::raydium_cpmm::RAYDIUM_CPMM_PROGRAM_ID;
 
 /// Initialize the platform with owner, operator, treasury, and fee configuration
 /// PDA seeds: ["platform"]
@@ -9,12 +14,18 @@ pub fn initialize_platform(
     treasury: Pubkey,
     fee_bps: u16,
 ) -> Result<()> {
+    require!(fee_bps <= PlatformConfig::MAX_FEE_BPS, PresaleError::FeeTooHigh);
+
     let platform = &mut ctx.accounts.platform;
     platform.owner = ctx.accounts.owner.key();
     platform.operator = operator;
     platform.treasury = treasury;
     platform.fee_bps = fee_bps;
+    platform.pending_owner = Pubkey::default();
+    platform.voucher_signer = Pubkey::default();
+    platform.pumpfun_program = PUMPFUN_PROGRAM_ID;
+    platform.pumpswap_program = PUMPSWAP_PROGRAM_ID;
+    platform.raydium_cpmm_program = RAYDIUM_CPMM_PROGRAM_ID;
     platform.bump = ctx.bumps.platform;
     Ok(())
 }
-
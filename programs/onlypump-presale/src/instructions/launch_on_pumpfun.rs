@@ -0,0 +1,198 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::token::{self, accessor, InitializeAccount3, TokenAccount};
+use crate::adapters::pumpfun;
+use crate::state::accounts::LaunchOnPumpfun;
+use crate::state::data::{LaunchOnPumpfunParams, PresalePhase};
+use crate::errors::PresaleError;
+use crate::events::PumpfunLaunched;
+use crate::utils::{apply_bps, collect_fee, drain_vault, transfer_lamports, BPS_DENOMINATOR};
+
+/// Create the presale token on pump.fun and buy into token_vault with the raised SOL
+/// Admin-only (owner or operator)
+/// Replaces withdraw_for_launch + a manual buy: the raised SOL never leaves program custody.
+/// Merges the VIP raise into public_sol_vault and hands the raise, less a reserve for the
+/// platform fee, to the launch_buyer PDA (["launch_buyer", presale]) which signs pump.fun
+/// `create` (with the reserved mint keypair) and `buy`. pump.fun only accepts a system-owned
+/// buyer, hence the extra PDA instead of the presale account itself.
+/// Bought tokens land in token_vault (created here, same PDA as initialize_vaults) and count
+/// as funded; unspent SOL goes back to public_sol_vault. The platform fee is charged on the
+/// SOL actually spent; what stays in the vault is charged when it leaves (migration).
+/// Requires a finalized presale whose vote outcome is Launch, before launch_deadline_ts,
+/// funding being sealed and any withdraw_for_launch.
+pub fn launch_on_pumpfun(
+    ctx: Context<LaunchOnPumpfun>,
+    params: LaunchOnPumpfunParams,
+) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let presale = &ctx.accounts.presale;
+    let presale_key = presale.key();
+
+    require!(presale.is_finalized, PresaleError::PresaleNotFinalized);
+    require!(!presale.funding_sealed, PresaleError::FundingSealed);
+    require!(
+        presale.outcome == crate::instructions::vote::outcome::LAUNCH,
+        PresaleError::Unauthorized
+    );
+    require!(params.token_amount > 0, PresaleError::InvalidPresaleConfig);
+    require!(
+        Clock::get()?.unix_timestamp <= presale.launch_deadline_ts,
+        PresaleError::LaunchDeadlinePassed
+    );
+    // The launch spends the whole raise: not after withdraw_for_launch (escrow tranche or not)
+    require!(
+        presale.escrow_released_bps == 0,
+        PresaleError::LaunchFundsAlreadyWithdrawn
+    );
+
+    let public_sol_vault = ctx.accounts.public_sol_vault.to_account_info();
+    drain_vault(&ctx.accounts.vip_sol_vault.to_account_info(), &public_sol_vault)?;
+
+    // Keep the vault rent-exempt so unspent SOL can be returned to it
+    let rent_reserve = Rent::get()?.minimum_balance(public_sol_vault.data_len());
+    let gross = public_sol_vault
+        .lamports()
+        .checked_sub(rent_reserve)
+        .ok_or(PresaleError::InsufficientFunds)?;
+    require!(gross > 0, PresaleError::InsufficientFunds);
+
+    // The fee on whatever gets spent must still be payable from the vault afterwards
+    let fee_reserve = apply_bps(gross, ctx.accounts.platform.fee_bps)?;
+    let budget = gross.checked_sub(fee_reserve).ok_or(PresaleError::MathOverflow)?;
+    require!(params.max_sol_cost <= budget, PresaleError::InsufficientFunds);
+
+    let launch_buyer = ctx.accounts.launch_buyer.to_account_info();
+    transfer_lamports(&public_sol_vault, &launch_buyer, budget)?;
+
+    let launch_buyer_seeds: &[&[u8]] = &[
+        b"launch_buyer",
+        presale_key.as_ref(),
+        &[ctx.bumps.launch_buyer],
+    ];
+
+    pumpfun::create(
+        &pumpfun::CreateAccounts {
+            mint: &ctx.accounts.mint.to_account_info(),
+            mint_authority: &ctx.accounts.pumpfun_mint_authority.to_account_info(),
+            bonding_curve: &ctx.accounts.bonding_curve.to_account_info(),
+            associated_bonding_curve: &ctx.accounts.associated_bonding_curve.to_account_info(),
+            global: &ctx.accounts.pumpfun_global.to_account_info(),
+            mpl_token_metadata: &ctx.accounts.mpl_token_metadata.to_account_info(),
+            metadata: &ctx.accounts.metadata.to_account_info(),
+            user: &launch_buyer,
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+            rent: &ctx.accounts.rent.to_account_info(),
+            event_authority: &ctx.accounts.pumpfun_event_authority.to_account_info(),
+            program: &ctx.accounts.pumpfun_program.to_account_info(),
+        },
+        params.name,
+        params.symbol,
+        params.uri,
+        presale.authority,
+        &[launch_buyer_seeds],
+    )?;
+
+    // The mint did not exist before `create`, so token_vault is initialized by hand
+    let token_vault = ctx.accounts.token_vault.to_account_info();
+    let token_vault_seeds: &[&[u8]] = &[
+        b"token_vault",
+        presale_key.as_ref(),
+        &[ctx.bumps.token_vault],
+    ];
+    if token_vault.data_is_empty() {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: token_vault.clone(),
+                },
+                &[token_vault_seeds],
+            ),
+            Rent::get()?.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &ctx.accounts.token_program.key(),
+        )?;
+        token::initialize_account3(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeAccount3 {
+                account: token_vault.clone(),
+                mint: ctx.accounts.mint.to_account_info(),
+                authority: token_vault.clone(),
+            },
+        ))?;
+    }
+    let balance_before = accessor::amount(&token_vault)?;
+
+    pumpfun::buy(
+        &pumpfun::BuyAccounts {
+            global: &ctx.accounts.pumpfun_global.to_account_info(),
+            fee_recipient: &ctx.accounts.pumpfun_fee_recipient.to_account_info(),
+            mint: &ctx.accounts.mint.to_account_info(),
+            bonding_curve: &ctx.accounts.bonding_curve.to_account_info(),
+            associated_bonding_curve: &ctx.accounts.associated_bonding_curve.to_account_info(),
+            associated_user: &token_vault,
+            user: &launch_buyer,
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            creator_vault: &ctx.accounts.creator_vault.to_account_info(),
+            event_authority: &ctx.accounts.pumpfun_event_authority.to_account_info(),
+            program: &ctx.accounts.pumpfun_program.to_account_info(),
+        },
+        params.token_amount,
+        params.max_sol_cost,
+        &[launch_buyer_seeds],
+    )?;
+
+    let tokens_bought = accessor::amount(&token_vault)?
+        .checked_sub(balance_before)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    // Return whatever create + buy did not spend
+    let unspent = launch_buyer.lamports();
+    if unspent > 0 {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: launch_buyer.clone(),
+                    to: public_sol_vault.clone(),
+                },
+                &[launch_buyer_seeds],
+            ),
+            unspent,
+        )?;
+    }
+    let sol_spent = budget.checked_sub(unspent).ok_or(PresaleError::MathOverflow)?;
+    let fee = collect_fee(
+        presale_key,
+        &public_sol_vault,
+        &ctx.accounts.fee_vault.to_account_info(),
+        sol_spent,
+        ctx.accounts.platform.fee_bps,
+    )?;
+
+    let presale = &mut ctx.accounts.presale;
+    presale.funded_tokens = presale
+        .funded_tokens
+        .checked_add(tokens_bought)
+        .ok_or(PresaleError::MathOverflow)?;
+    // Nothing is left in escrow: withdraw_for_launch / release_escrow are closed from here on
+    presale.escrow_released_bps = BPS_DENOMINATOR as u16;
+    if presale.phase != PresalePhase::Launched {
+        presale.transition_to(presale_key, PresalePhase::Launched)?;
+    }
+
+    emit!(PumpfunLaunched {
+        presale: presale_key,
+        mint: presale.mint,
+        sol_spent,
+        tokens_bought,
+        platform_fee: fee,
+    });
+
+    Ok(())
+}
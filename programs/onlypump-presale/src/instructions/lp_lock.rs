@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, TransferChecked};
+use crate::state::accounts::{BurnLp, UnlockLp};
+use crate::errors::PresaleError;
+use crate::events::{LpBurned, LpUnlocked};
+
+/// Release the locked LP tokens to presale.lp_lock.recipient
+/// Permissionless: only works once lp_unlock_ts has passed, and always pays the
+/// configured recipient
+pub fn unlock_lp(ctx: Context<UnlockLp>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let presale = &ctx.accounts.presale;
+    require!(presale.is_migrated, PresaleError::PresaleNotMigrated);
+    require!(
+        Clock::get()?.unix_timestamp >= presale.lp_unlock_ts,
+        PresaleError::LpStillLocked
+    );
+
+    let amount = presale.lp_locked_amount;
+    require!(amount > 0, PresaleError::NothingToClaim);
+
+    let presale_key = presale.key();
+    let lp_authority_seeds: &[&[u8]] = &[
+        b"lp_authority",
+        presale_key.as_ref(),
+        &[ctx.bumps.lp_authority],
+    ];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.lp_vault.to_account_info(),
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
+                authority: ctx.accounts.lp_authority.to_account_info(),
+            },
+            &[lp_authority_seeds],
+        ),
+        amount,
+        ctx.accounts.lp_mint.decimals,
+    )?;
+
+    let presale = &mut ctx.accounts.presale;
+    presale.lp_locked_amount = 0;
+
+    emit!(LpUnlocked {
+        presale: presale_key,
+        recipient: presale.lp_lock.recipient,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Burn the locked LP tokens, making the pool's liquidity permanent
+/// Admin-only (owner or operator); allowed while the lock is still running
+pub fn burn_lp(ctx: Context<BurnLp>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let presale = &ctx.accounts.presale;
+    require!(presale.is_migrated, PresaleError::PresaleNotMigrated);
+
+    let amount = presale.lp_locked_amount;
+    require!(amount > 0, PresaleError::NothingToClaim);
+
+    let presale_key = presale.key();
+    let lp_authority_seeds: &[&[u8]] = &[
+        b"lp_authority",
+        presale_key.as_ref(),
+        &[ctx.bumps.lp_authority],
+    ];
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.lp_vault.to_account_info(),
+                authority: ctx.accounts.lp_authority.to_account_info(),
+            },
+            &[lp_authority_seeds],
+        ),
+        amount,
+    )?;
+
+    let presale = &mut ctx.accounts.presale;
+    presale.lp_locked_amount = 0;
+
+    emit!(LpBurned {
+        presale: presale_key,
+        amount,
+    });
+
+    Ok(())
+}
@@ -1,103 +1,251 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer};
+use anchor_lang::system_program;
+use anchor_spl::token::{self, accessor, SyncNative, Transfer};
+use anchor_spl::token_2022;
+use crate::adapters::amm::{self, PoolDeposit};
 use crate::state::accounts::MigrateAndCreateLp;
 use crate::errors::PresaleError;
-use crate::events::MigrateAndCreateLpEvent;
-use crate::instructions::vote::phase;
+use crate::events::{LpLocked, MigrateAndCreateLpEvent, RevenueShared};
+use crate::revenue::split_revenue;
+use crate::state::data::{MigrateAndCreateLpParams, PresalePhase};
+use crate::utils::{collect_fee, drain_vault, transfer_lamports};
 
-/// Migrate presale and create LP
+/// Migrate presale and create the LP pool
 /// Admin-only
-/// Preconditions: is_finalized == true, is_migrated == false
+/// Preconditions: is_finalized == true, funding_sealed == true, is_migrated == false
 /// Actions:
-/// - Transfer lp_token_allocation (300M) from token_vault for LP
-/// - Take lp_sol_amount from public_sol_vault to pair with tokens
-/// - Transfer ecosystem_allocation (100M) to ecosystem_vault
+/// - Merge the VIP raise (vip_sol_vault) into public_sol_vault
+/// - Skim the platform fee on the SOL leaving public_sol_vault into fee_vault
+/// - Move funded_lp_tokens and lp_sol_amount (wrapped) into lp_authority's deposit accounts
+/// - Create the pool on params.amm via CPI, signed by the lp_authority PDA
+///   (["lp_authority", presale]); the LP tokens are minted to lp_authority and locked
+///   there for presale.lp_lock.lock_seconds (see unlock_lp / burn_lp).
+///   The AMM-specific pool accounts are passed as remaining accounts (see crate::adapters).
+/// - Transfer funded_ecosystem_tokens (if any) to ecosystem_vault
 /// - Set is_migrated = true
-/// - Send leftover SOL to treasury
-/// Note: Actual LP creation CPI is stubbed for now
-pub fn migrate_and_create_lp(
-    ctx: Context<MigrateAndCreateLp>,
-    lp_sol_amount: u64,
+/// - Split leftover SOL between creator, treasury and VIP rewards per InfluencerConfig
+///   (all of it to treasury without a config)
+pub fn migrate_and_create_lp<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateAndCreateLp<'info>>,
+    params: MigrateAndCreateLpParams,
 ) -> Result<()> {
-    ctx.accounts.validate()?;
+    ctx.accounts.validate(params.amm)?;
 
+    let presale_key = ctx.accounts.presale.key();
     let presale = &mut ctx.accounts.presale;
 
     require!(presale.is_finalized, PresaleError::PresaleNotFinalized);
-    // If a vote occurred, ensure we are in the Launchable phase (or already launched)
-    if presale.outcome != crate::instructions::vote::outcome::UNDECIDED {
-        require!(
-            presale.phase == phase::LAUNCHABLE || presale.phase == phase::LAUNCHED,
-            PresaleError::PresaleNotFinalized
-        );
-    }
+    require!(presale.funding_sealed, PresaleError::FundingNotSealed);
     require!(!presale.is_migrated, PresaleError::PresaleAlreadyMigrated);
+    // Escrow settles first (release_escrow), so migration never spends the escrowed remainder
+    require!(!presale.escrow_pending(), PresaleError::EscrowPending);
+
+    // Funding can only be sealed once the vaults exist, which needs a passed launch vote:
+    // initialize_vaults or launch_on_pumpfun have already moved the presale to Launched.
+    require!(
+        presale.phase == PresalePhase::Launchable || presale.phase == PresalePhase::Launched,
+        PresaleError::PresaleNotFinalized
+    );
+    if presale.phase != PresalePhase::Launched {
+        presale.transition_to(presale_key, PresalePhase::Launched)?;
+    }
 
-    // Transfer LP tokens (300M) from token_vault to lp_token_account
-    let presale_key = presale.key();
     let token_vault_seeds = &[
         b"token_vault",
         presale_key.as_ref(),
         &[ctx.bumps.token_vault],
     ];
 
+    // Everything left in public_sol_vault exits here (LP + treasury), so the
+    // platform fee is taken on the full balance (public + VIP) before splitting it up
+    let public_sol_vault = ctx.accounts.public_sol_vault.to_account_info();
+    drain_vault(&ctx.accounts.vip_sol_vault.to_account_info(), &public_sol_vault)?;
+    let fee = collect_fee(
+        presale_key,
+        &public_sol_vault,
+        &ctx.accounts.fee_vault.to_account_info(),
+        public_sol_vault.lamports(),
+        ctx.accounts.platform.fee_bps,
+    )?;
+
+    // Fund lp_authority's deposit accounts: LP tokens, wrapped SOL and the pool creation budget
     token::transfer(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
                 from: ctx.accounts.token_vault.to_account_info(),
-                to: ctx.accounts.lp_token_account.to_account_info(),
+                to: ctx.accounts.lp_base_account.to_account_info(),
                 authority: ctx.accounts.token_vault_authority.to_account_info(),
             },
             &[token_vault_seeds],
         ),
-        presale.lp_token_allocation,
+        presale.funded_lp_tokens,
     )?;
 
-    // Transfer SOL from public_sol_vault to lp_sol_account
+    let lp_sol_needed = params
+        .lp_sol_amount
+        .checked_add(params.pool_creation_lamports)
+        .ok_or(PresaleError::MathOverflow)?;
     require!(
-        ctx.accounts.public_sol_vault.lamports() >= lp_sol_amount,
+        public_sol_vault.lamports() >= lp_sol_needed,
         PresaleError::InsufficientFunds
     );
 
-    **ctx.accounts.public_sol_vault.try_borrow_mut_lamports()? -= lp_sol_amount;
-    **ctx.accounts.lp_sol_account.try_borrow_mut_lamports()? += lp_sol_amount;
+    transfer_lamports(
+        &public_sol_vault,
+        &ctx.accounts.lp_quote_account.to_account_info(),
+        params.lp_sol_amount,
+    )?;
+    token::sync_native(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        SyncNative {
+            account: ctx.accounts.lp_quote_account.to_account_info(),
+        },
+    ))?;
 
-    // Transfer ecosystem tokens (100M) to ecosystem_vault
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.token_vault.to_account_info(),
-                to: ctx.accounts.ecosystem_vault.to_account_info(),
-                authority: ctx.accounts.token_vault_authority.to_account_info(),
-            },
-            &[token_vault_seeds],
-        ),
-        presale.ecosystem_allocation,
+    let lp_authority = ctx.accounts.lp_authority.to_account_info();
+    transfer_lamports(&public_sol_vault, &lp_authority, params.pool_creation_lamports)?;
+
+    let lp_authority_seeds: &[&[u8]] = &[
+        b"lp_authority",
+        presale_key.as_ref(),
+        &[ctx.bumps.lp_authority],
+    ];
+    let lp_token_account = amm::create_pool(
+        params.amm,
+        &ctx.accounts.amm_program.to_account_info(),
+        &PoolDeposit {
+            creator: &lp_authority,
+            base_mint: &ctx.accounts.mint.to_account_info(),
+            quote_mint: &ctx.accounts.quote_mint.to_account_info(),
+            creator_base_account: &ctx.accounts.lp_base_account.to_account_info(),
+            creator_quote_account: &ctx.accounts.lp_quote_account.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+            rent: &ctx.accounts.rent.to_account_info(),
+            base_amount: presale.funded_lp_tokens,
+            quote_amount: params.lp_sol_amount,
+        },
+        ctx.remaining_accounts,
+        &[lp_authority_seeds],
     )?;
 
-    // Transfer leftover SOL to treasury
-    let remaining_sol = ctx.accounts.public_sol_vault.lamports();
-    if remaining_sol > 0 {
-        **ctx.accounts.public_sol_vault.try_borrow_mut_lamports()? -= remaining_sol;
-        **ctx.accounts.treasury.try_borrow_mut_lamports()? += remaining_sol;
+    // The LP tokens must end up with lp_authority, not some caller-chosen account
+    // (PumpSwap LP mints are Token-2022, CPMM's are SPL token)
+    require!(
+        (lp_token_account.owner == &token::ID || lp_token_account.owner == &token_2022::ID)
+            && accessor::authority(&lp_token_account)? == lp_authority.key(),
+        PresaleError::LpNotMinted
+    );
+    let lp_minted = accessor::amount(&lp_token_account)?;
+    require!(lp_minted > 0, PresaleError::LpNotMinted);
+    let lp_mint = accessor::mint(&lp_token_account)?;
+
+    // LP tokens stay in lp_authority's account until unlock_lp (after the lock) or burn_lp
+    let unlock_ts = Clock::get()?
+        .unix_timestamp
+        .checked_add(presale.lp_lock.lock_seconds)
+        .ok_or(PresaleError::MathOverflow)?;
+    presale.lp_mint = lp_mint;
+    presale.lp_vault = lp_token_account.key();
+    presale.lp_locked_amount = lp_minted;
+    presale.lp_unlock_ts = unlock_ts;
+    emit!(LpLocked {
+        presale: presale_key,
+        lp_mint,
+        lp_vault: presale.lp_vault,
+        amount: lp_minted,
+        unlock_ts,
+        recipient: presale.lp_lock.recipient,
+    });
+
+    // Return whatever the pool creation did not spend
+    let unspent = lp_authority.lamports();
+    if unspent > 0 {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: lp_authority.clone(),
+                    to: public_sol_vault.clone(),
+                },
+                &[lp_authority_seeds],
+            ),
+            unspent,
+        )?;
     }
 
+    // Transfer ecosystem tokens to ecosystem_vault (skipped when there is no ecosystem bucket)
+    if presale.funded_ecosystem_tokens > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.token_vault.to_account_info(),
+                    to: ctx.accounts.ecosystem_vault.to_account_info(),
+                    authority: ctx.accounts.token_vault_authority.to_account_info(),
+                },
+                &[token_vault_seeds],
+            ),
+            presale.funded_ecosystem_tokens,
+        )?;
+    }
+
+    // Split leftover SOL between creator, treasury and VIP holders
+    let remaining_sol = public_sol_vault.lamports();
+    let split = split_revenue(
+        ctx.accounts.influencer_config.as_deref(),
+        remaining_sol,
+        presale.vip_raised_lamports > 0,
+    )?;
+
+    if let (Some(vip_pool), Some(vip_rewards_vault)) = (
+        ctx.accounts.vip_pool.as_mut(),
+        ctx.accounts.vip_rewards_vault.as_ref(),
+    ) {
+        transfer_lamports(
+            &public_sol_vault,
+            &vip_rewards_vault.to_account_info(),
+            split.vip_lamports,
+        )?;
+        vip_pool.total_rewards_lamports = vip_pool
+            .total_rewards_lamports
+            .checked_add(split.vip_lamports)
+            .ok_or(PresaleError::MathOverflow)?;
+    }
+    if let (Some(influencer_config), Some(creator)) = (
+        ctx.accounts.influencer_config.as_ref(),
+        ctx.accounts.creator.as_ref(),
+    ) {
+        transfer_lamports(&public_sol_vault, &creator.to_account_info(), split.creator_lamports)?;
+        emit!(RevenueShared {
+            presale: presale_key,
+            creator: influencer_config.creator,
+            creator_lamports: split.creator_lamports,
+            platform_lamports: split.platform_lamports,
+            vip_rewards_lamports: split.vip_lamports,
+        });
+    }
+    transfer_lamports(
+        &public_sol_vault,
+        &ctx.accounts.treasury.to_account_info(),
+        split.platform_lamports,
+    )?;
+
     // Mark as migrated
     presale.is_migrated = true;
-    presale.phase = phase::LAUNCHED;
-
-    // TODO: Stub for actual LP creation CPI
-    // In production, this would call Raydium or PumpSwap to create the LP
-    // For now, tokens and SOL are in lp_token_account and lp_sol_account ready for LP creation
 
     emit!(MigrateAndCreateLpEvent {
         presale: presale.key(),
-        lp_tokens: presale.lp_token_allocation,
-        lp_sol: lp_sol_amount,
-        ecosystem_tokens: presale.ecosystem_allocation,
-        remaining_sol_to_treasury: remaining_sol,
+        amm: params.amm,
+        lp_mint,
+        lp_minted,
+        lp_tokens: presale.funded_lp_tokens,
+        lp_sol: params.lp_sol_amount,
+        ecosystem_tokens: presale.funded_ecosystem_tokens,
+        remaining_sol_to_treasury: split.platform_lamports,
+        platform_fee: fee,
     });
 
     Ok(())
@@ -1,24 +1,48 @@
 pub mod initialize_platform;
+pub mod sweep_fees;
+pub mod update_platform_config;
+pub mod transfer_ownership;
 pub mod create_presale;
 pub mod initialize_vaults;
 pub mod fund_presale_tokens;
+pub mod seal_funding;
 pub mod withdraw_for_launch;
+pub mod escrow;
+pub mod creator_bond;
+pub mod launch_on_pumpfun;
 pub mod whitelist_user;
+pub mod set_whitelist_root;
 pub mod contribute_public;
+pub mod vip_pool;
+pub mod configure_influencer;
 pub mod finalize_presale;
 pub mod migrate_and_create_lp;
+pub mod lp_lock;
 pub mod claim_tokens;
+pub mod get_vested_amount;
 pub mod vote;
 
 pub use initialize_platform::*;
+pub use sweep_fees::*;
+pub use update_platform_config::*;
+pub use transfer_ownership::*;
 pub use create_presale::*;
 pub use initialize_vaults::*;
 pub use fund_presale_tokens::*;
+pub use seal_funding::*;
 pub use withdraw_for_launch::*;
+pub use escrow::*;
+pub use creator_bond::*;
+pub use launch_on_pumpfun::*;
 pub use whitelist_user::*;
+pub use set_whitelist_root::*;
 pub use contribute_public::*;
+pub use vip_pool::*;
+pub use configure_influencer::*;
 pub use finalize_presale::*;
 pub use migrate_and_create_lp::*;
+pub use lp_lock::*;
 pub use claim_tokens::*;
+pub use get_vested_amount::*;
 pub use vote::*;
 
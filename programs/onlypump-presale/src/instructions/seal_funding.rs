@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::state::accounts::SealFunding;
+use crate::state::data::ClaimMode;
+use crate::errors::PresaleError;
+use crate::events::FundingSealed;
+
+/// Record the final public/LP/ecosystem split of the funded tokens and lock funding
+/// Admin-only, after finalize_presale
+/// The split must add up to presale.funded_tokens, which the vault must hold. The vault may
+/// hold more: anyone can transfer tokens into it, so its balance is only a lower bound.
+/// In FixedPrice mode the split must be exactly the declared allocation (public_token_cap,
+/// lp_token_allocation, ecosystem_allocation), which covers every contribution-time allocation.
+/// After sealing: no more fund_presale_tokens or withdraw_for_launch; claims are allowed.
+pub fn seal_funding(
+    ctx: Context<SealFunding>,
+    public_tokens: u64,
+    lp_tokens: u64,
+    ecosystem_tokens: u64,
+) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let presale_key = ctx.accounts.presale.key();
+    let vault_amount = ctx.accounts.token_vault.amount;
+    let presale = &mut ctx.accounts.presale;
+
+    require!(presale.is_finalized, PresaleError::PresaleNotFinalized);
+    require!(!presale.funding_sealed, PresaleError::FundingSealed);
+
+    let total = public_tokens
+        .checked_add(lp_tokens)
+        .and_then(|total| total.checked_add(ecosystem_tokens))
+        .ok_or(PresaleError::MathOverflow)?;
+    require!(total == presale.funded_tokens, PresaleError::FundingAmountMismatch);
+    require!(vault_amount >= total, PresaleError::FundingAmountMismatch);
+    if presale.claim_mode == ClaimMode::FixedPrice {
+        require!(
+            public_tokens == presale.public_token_cap
+                && lp_tokens == presale.lp_token_allocation
+                && ecosystem_tokens == presale.ecosystem_allocation,
+            PresaleError::FundingAmountMismatch
+        );
+        require!(
+            public_tokens >= presale.total_tokens_allocated,
+            PresaleError::FundingAmountMismatch
+        );
+    }
+
+    presale.funded_public_tokens = public_tokens;
+    presale.funded_lp_tokens = lp_tokens;
+    presale.funded_ecosystem_tokens = ecosystem_tokens;
+    presale.funding_sealed = true;
+
+    emit!(FundingSealed {
+        presale: presale_key,
+        public_tokens,
+        lp_tokens,
+        ecosystem_tokens,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::accounts::SetWhitelistRoot;
+use crate::errors::PresaleError;
+use crate::events::WhitelistRootSet;
+
+/// Set (or clear with all zeros) the Merkle allowlist root for a whitelist tier
+/// Admin-only
+/// Leaves are keccak256(user || pool || tier || max_contribution_lamports LE) with pool 0 = public,
+/// 1 = VIP (see AllowlistPool); pairs hashed sorted
+pub fn set_whitelist_root(ctx: Context<SetWhitelistRoot>, tier: u8, root: [u8; 32]) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let presale = &mut ctx.accounts.presale;
+    let index = (tier as usize)
+        .checked_sub(1)
+        .filter(|index| *index < presale.whitelist_roots.len())
+        .ok_or(PresaleError::InvalidTier)?;
+    presale.whitelist_roots[index] = root;
+
+    emit!(WhitelistRootSet {
+        presale: presale.key(),
+        tier,
+        root,
+    });
+
+    Ok(())
+}
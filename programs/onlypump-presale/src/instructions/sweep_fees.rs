@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use crate::state::accounts::SweepFees;
+use crate::events::FeesSwept;
+use crate::errors::PresaleError;
+use crate::utils::transfer_lamports;
+
+/// Sweep accrued platform fees from fee_vault to platform.treasury
+/// Owner-only
+/// Leaves the rent-exempt minimum in fee_vault so the PDA stays alive
+pub fn sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let fee_vault = ctx.accounts.fee_vault.to_account_info();
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(fee_vault.data_len());
+    let amount = fee_vault.lamports().saturating_sub(rent_exempt_minimum);
+    require!(amount > 0, PresaleError::NothingToClaim);
+
+    transfer_lamports(&fee_vault, &ctx.accounts.treasury.to_account_info(), amount)?;
+
+    emit!(FeesSwept {
+        treasury: ctx.accounts.treasury.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::accounts::{AcceptOwner, ProposeOwner};
+use crate::events::{OwnershipTransferProposed, OwnershipTransferred};
+
+/// Propose a new platform owner (step 1 of 2)
+/// Owner-only
+/// Passing Pubkey::default() cancels a pending proposal
+pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let platform = &mut ctx.accounts.platform;
+    platform.pending_owner = new_owner;
+
+    emit!(OwnershipTransferProposed {
+        owner: platform.owner,
+        pending_owner: new_owner,
+    });
+
+    Ok(())
+}
+
+/// Accept a pending ownership transfer (step 2 of 2)
+/// Must be signed by platform.pending_owner
+pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let platform = &mut ctx.accounts.platform;
+    let previous_owner = platform.owner;
+    platform.owner = platform.pending_owner;
+    platform.pending_owner = Pubkey::default();
+
+    emit!(OwnershipTransferred {
+        previous_owner,
+        new_owner: platform.owner,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::accounts::UpdatePlatformConfig;
+use crate::state::data::{PlatformConfig, UpdatePlatformConfigParams};
+use crate::errors::PresaleError;
+use crate::events::PlatformConfigUpdated;
+
+/// Update operator, treasury, fee_bps, voucher_signer and/or the pump.fun / AMM program ids on the platform config
+/// Owner-only
+/// Fields left as `None` keep their current value
+pub fn update_platform_config(
+    ctx: Context<UpdatePlatformConfig>,
+    params: UpdatePlatformConfigParams,
+) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let platform = &mut ctx.accounts.platform;
+
+    if let Some(operator) = params.operator {
+        platform.operator = operator;
+    }
+    if let Some(treasury) = params.treasury {
+        platform.treasury = treasury;
+    }
+    if let Some(fee_bps) = params.fee_bps {
+        require!(fee_bps <= PlatformConfig::MAX_FEE_BPS, PresaleError::FeeTooHigh);
+        platform.fee_bps = fee_bps;
+    }
+    if let Some(voucher_signer) = params.voucher_signer {
+        platform.voucher_signer = voucher_signer;
+    }
+    if let Some(pumpfun_program) = params.pumpfun_program {
+        platform.pumpfun_program = pumpfun_program;
+    }
+    if let Some(pumpswap_program) = params.pumpswap_program {
+        platform.pumpswap_program = pumpswap_program;
+    }
+    if let Some(raydium_cpmm_program) = params.raydium_cpmm_program {
+        platform.raydium_cpmm_program = raydium_cpmm_program;
+    }
+
+    emit!(PlatformConfigUpdated {
+        operator: platform.operator,
+        treasury: platform.treasury,
+        fee_bps: platform.fee_bps,
+        voucher_signer: platform.voucher_signer,
+        pumpfun_program: platform.pumpfun_program,
+        pumpswap_program: platform.pumpswap_program,
+        raydium_cpmm_program: platform.raydium_cpmm_program,
+    });
+
+    Ok(())
+}
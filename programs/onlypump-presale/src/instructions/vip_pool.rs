@@ -0,0 +1,306 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use crate::state::accounts::*;
+use crate::state::data::{AllowlistPool, AllowlistProof, ContributionWindow, PresalePhase};
+use crate::allowlist::resolve_whitelist_grant;
+use crate::errors::PresaleError;
+use crate::events::{ClaimTokensEvent, ContributeVipEvent, VipRewardsClaimed};
+use crate::utils::transfer_lamports;
+
+/// Open the VIP round for a presale
+/// Admin-only, before the presale is finalized
+/// PDA seeds: ["vip_pool", presale_pubkey], SOL vault: ["vip_sol_vault", presale_pubkey],
+/// rewards vault: ["vip_rewards_vault", presale_pubkey]
+pub fn create_vip_pool(
+    ctx: Context<CreateVipPool>,
+    price_lamports_per_token: u64,
+    hard_cap_lamports: u64,
+) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    require!(
+        !ctx.accounts.presale.is_finalized,
+        PresaleError::PresaleAlreadyFinalized
+    );
+    require!(
+        price_lamports_per_token > 0 && hard_cap_lamports > 0,
+        PresaleError::InvalidPresaleConfig
+    );
+
+    let vip_pool = &mut ctx.accounts.vip_pool;
+    vip_pool.presale = ctx.accounts.presale.key();
+    vip_pool.price_lamports_per_token = price_lamports_per_token;
+    vip_pool.hard_cap_lamports = hard_cap_lamports;
+    vip_pool.total_contributions = 0;
+    vip_pool.total_tokens_allocated = 0;
+    vip_pool.total_rewards_lamports = 0;
+    vip_pool.bump = ctx.bumps.vip_pool;
+
+    Ok(())
+}
+
+/// Whitelisted user contributes SOL to the VIP round
+/// Open whenever contribute_public is (whitelist or public window), at the VIP price.
+/// Requires a whitelist grant (WhitelistEntry, Merkle proof or voucher); the grant's
+/// max_contribution_lamports caps the wallet's cumulative VIP contribution.
+/// VIP tokens count against the presale's public_token_cap.
+/// PDA seeds for VipPosition: ["vip_position", vip_pool_pubkey, user_pubkey]
+pub fn contribute_vip(
+    ctx: Context<ContributeVip>,
+    amount_lamports: u64,
+    allowlist_proof: Option<AllowlistProof>,
+) -> Result<()> {
+    let presale = &ctx.accounts.presale;
+    let presale_key = presale.key();
+
+    require!(!presale.is_finalized, PresaleError::PresaleAlreadyFinalized);
+    require!(
+        presale.phase == PresalePhase::PublicActive,
+        PresaleError::PresaleNotActive
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        presale.contribution_window(now) != ContributionWindow::Closed,
+        PresaleError::PresaleNotActive
+    );
+    require!(amount_lamports > 0, PresaleError::ContributionBelowMinimum);
+
+    let grant = resolve_whitelist_grant(
+        AllowlistPool::Vip,
+        presale,
+        &ctx.accounts.platform,
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.user.key(),
+        ctx.accounts.whitelist.as_deref(),
+        allowlist_proof.as_ref(),
+        now,
+    )?
+    .ok_or(PresaleError::NotWhitelisted)?;
+    require!(grant.tier >= 1, PresaleError::NotWhitelisted);
+    if let Some(nonce) = grant.voucher_nonce {
+        require!(
+            nonce > ctx.accounts.vip_position.voucher_nonce,
+            PresaleError::VoucherNonceReused
+        );
+    }
+
+    let wallet_total = ctx
+        .accounts
+        .vip_position
+        .contribution_lamports
+        .checked_add(amount_lamports)
+        .ok_or(PresaleError::MathOverflow)?;
+    require!(
+        grant.max_contribution_lamports == 0
+            || wallet_total <= grant.max_contribution_lamports,
+        PresaleError::WhitelistCapExceeded
+    );
+
+    let vip_pool = &ctx.accounts.vip_pool;
+    let new_vip_total = vip_pool
+        .total_contributions
+        .checked_add(amount_lamports)
+        .ok_or(PresaleError::MathOverflow)?;
+    require!(
+        new_vip_total <= vip_pool.hard_cap_lamports,
+        PresaleError::HardCapExceeded
+    );
+
+    // tokens = amount_lamports * 10^token_decimals / VIP price
+    let tokens_to_allocate =
+        presale.tokens_for_lamports(amount_lamports, vip_pool.price_lamports_per_token)?;
+    let new_total_allocated = presale
+        .total_tokens_allocated
+        .checked_add(tokens_to_allocate)
+        .ok_or(PresaleError::MathOverflow)?;
+    require!(
+        new_total_allocated <= presale.public_token_cap,
+        PresaleError::TokenCapExceeded
+    );
+
+    anchor_lang::solana_program::program::invoke(
+        &anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.user.key(),
+            &ctx.accounts.vip_sol_vault.key(),
+            amount_lamports,
+        ),
+        &[
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.vip_sol_vault.to_account_info(),
+        ],
+    )?;
+
+    let presale_mut = &mut ctx.accounts.presale;
+    presale_mut.vip_raised_lamports = presale_mut
+        .vip_raised_lamports
+        .checked_add(amount_lamports)
+        .ok_or(PresaleError::MathOverflow)?;
+    presale_mut.total_tokens_allocated = new_total_allocated;
+
+    let vip_pool = &mut ctx.accounts.vip_pool;
+    vip_pool.total_contributions = new_vip_total;
+    vip_pool.total_tokens_allocated = vip_pool
+        .total_tokens_allocated
+        .checked_add(tokens_to_allocate)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    let position = &mut ctx.accounts.vip_position;
+    position.vip_pool = vip_pool.key();
+    position.user = ctx.accounts.user.key();
+    position.contribution_lamports = wallet_total;
+    position.tokens_allocated = position
+        .tokens_allocated
+        .checked_add(tokens_to_allocate)
+        .ok_or(PresaleError::MathOverflow)?;
+    if let Some(nonce) = grant.voucher_nonce {
+        position.voucher_nonce = nonce;
+    }
+    position.bump = ctx.bumps.vip_position;
+
+    emit!(ContributeVipEvent {
+        user: ctx.accounts.user.key(),
+        presale: presale_key,
+        amount_lamports,
+        tokens_allocated: tokens_to_allocate,
+        total_vip_raised: presale_mut.vip_raised_lamports,
+        total_tokens_allocated: presale_mut.total_tokens_allocated,
+        tier: grant.tier,
+    });
+
+    Ok(())
+}
+
+/// VIP contributor claims their vested tokens after migration
+/// Same rules (and vesting schedule) as claim_tokens, from the same token_vault
+pub fn claim_vip_tokens(ctx: Context<ClaimVipTokens>) -> Result<()> {
+    let presale = &ctx.accounts.presale;
+
+    require!(presale.funding_sealed, PresaleError::FundingNotSealed);
+    require!(presale.is_migrated, PresaleError::PresaleNotMigrated);
+
+    let position = &mut ctx.accounts.vip_position;
+    require_keys_eq!(position.user, ctx.accounts.user.key(), PresaleError::Unauthorized);
+
+    let entitlement =
+        presale.token_entitlement(position.contribution_lamports, position.tokens_allocated)?;
+    let vested = presale.vested_amount(entitlement, Clock::get()?.unix_timestamp)?;
+    let claimable = vested
+        .checked_sub(position.tokens_claimed)
+        .ok_or(PresaleError::NothingToClaim)?;
+
+    require!(claimable > 0, PresaleError::NothingToClaim);
+
+    let presale_key = presale.key();
+    let token_vault_seeds = &[
+        b"token_vault",
+        presale_key.as_ref(),
+        &[ctx.bumps.token_vault],
+    ];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.token_vault.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.token_vault_authority.to_account_info(),
+            },
+            &[token_vault_seeds],
+        ),
+        claimable,
+    )?;
+
+    position.tokens_claimed = position
+        .tokens_claimed
+        .checked_add(claimable)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    emit!(ClaimTokensEvent {
+        user: ctx.accounts.user.key(),
+        presale: presale_key,
+        tokens_claimed: claimable,
+    });
+
+    Ok(())
+}
+
+/// VIP contributor reclaims their SOL from vip_sol_vault when refunds are enabled
+/// Same rules as claim_refund (including escrow remainder refunds and the creator bond share)
+pub fn claim_vip_refund(ctx: Context<ClaimVipRefund>) -> Result<()> {
+    let presale = &mut ctx.accounts.presale;
+    let position = &mut ctx.accounts.vip_position;
+
+    require!(presale.refund_enabled, PresaleError::PresaleNotMigrated);
+    require!(
+        presale.phase == PresalePhase::Refundable,
+        PresaleError::PresaleNotMigrated
+    );
+    require_keys_eq!(position.user, ctx.accounts.user.key(), PresaleError::Unauthorized);
+    require!(!position.refunded, PresaleError::NothingToClaim);
+
+    let amount = presale.refund_amount(position.contribution_lamports)?;
+    let bond_share = presale.bond_share(position.contribution_lamports)?;
+    require!(amount > 0 || bond_share > 0, PresaleError::NothingToClaim);
+
+    if amount > 0 {
+        transfer_lamports(
+            &ctx.accounts.vip_sol_vault.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            amount,
+        )?;
+    }
+    if bond_share > 0 {
+        transfer_lamports(
+            &ctx.accounts.creator_bond.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            bond_share,
+        )?;
+    }
+
+    position.refunded = true;
+
+    // Refunded tokens no longer count against the global public cap
+    presale.total_tokens_allocated = presale
+        .total_tokens_allocated
+        .saturating_sub(position.tokens_allocated);
+
+    Ok(())
+}
+
+/// VIP contributor claims their pro-rata share of the VIP revenue share
+/// rewards_earned = total_rewards_lamports * contribution_lamports / total_contributions;
+/// can be called again whenever more rewards are paid into the vault
+pub fn claim_vip_rewards(ctx: Context<ClaimVipRewards>) -> Result<()> {
+    let position = &mut ctx.accounts.vip_position;
+    require_keys_eq!(position.user, ctx.accounts.user.key(), PresaleError::Unauthorized);
+
+    position.rewards_earned = ctx
+        .accounts
+        .vip_pool
+        .rewards_for(position.contribution_lamports)?;
+    let claimable = position
+        .rewards_earned
+        .checked_sub(position.rewards_claimed)
+        .ok_or(PresaleError::NothingToClaim)?;
+    require!(claimable > 0, PresaleError::NothingToClaim);
+
+    transfer_lamports(
+        &ctx.accounts.vip_rewards_vault.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
+        claimable,
+    )?;
+
+    position.rewards_claimed = position
+        .rewards_claimed
+        .checked_add(claimable)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    emit!(VipRewardsClaimed {
+        user: ctx.accounts.user.key(),
+        presale: ctx.accounts.presale.key(),
+        amount_lamports: claimable,
+    });
+
+    Ok(())
+}
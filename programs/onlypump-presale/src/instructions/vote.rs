@@ -1,16 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
 use crate::state::accounts::*;
+use crate::state::data::{Presale, PresalePhase, UserPosition, VipPosition, VoteReceipt};
 use crate::errors::PresaleError;
-
-// Phase constants for Presale.phase
-pub mod phase {
-    pub const PENDING: u8 = 0;
-    pub const PUBLIC_ACTIVE: u8 = 1;
-    pub const VOTING: u8 = 2;
-    pub const LAUNCHABLE: u8 = 3;
-    pub const REFUNDABLE: u8 = 4;
-    pub const LAUNCHED: u8 = 5;
-}
+use crate::events::{VoteDelegated, VoteResolved};
+use crate::utils::transfer_lamports;
 
 // Outcome constants for Presale.outcome
 pub mod outcome {
@@ -21,6 +15,10 @@ pub mod outcome {
 
 /// Start a community vote for a presale.
 /// Admin-controlled: backend decides when to trigger based on VIP/public state.
+/// Each call opens a new vote round with fresh tallies, so everyone can vote again.
+/// Rounds only restart the launch/refund vote: an ongoing vote can be restarted before its
+/// voting_ends_ts, a vote that has ended can only be resolved, and there are no further
+/// rounds once the presale left Voting.
 pub fn start_vote(ctx: Context<StartVote>, voting_ends_ts: i64) -> Result<()> {
     ctx.accounts.validate()?;
 
@@ -29,11 +27,23 @@ pub fn start_vote(ctx: Context<StartVote>, voting_ends_ts: i64) -> Result<()> {
         voting_ends_ts > clock.unix_timestamp,
         PresaleError::PresaleNotActive
     );
+    // A finished vote cannot be wiped before resolve_vote
+    require!(
+        ctx.accounts.presale.phase != PresalePhase::Voting
+            || clock.unix_timestamp < ctx.accounts.presale.voting_ends_ts,
+        PresaleError::PresaleNotActive
+    );
 
+    let presale_key = ctx.accounts.presale.key();
     let presale = &mut ctx.accounts.presale;
-    presale.phase = phase::VOTING;
+    presale.transition_to(presale_key, PresalePhase::Voting)?;
     presale.vote_yes_weight = 0;
     presale.vote_no_weight = 0;
+    presale.vote_turnout_lamports = 0;
+    presale.vote_round = presale
+        .vote_round
+        .checked_add(1)
+        .ok_or(PresaleError::MathOverflow)?;
     presale.voting_ends_ts = voting_ends_ts;
     presale.outcome = outcome::UNDECIDED;
 
@@ -41,12 +51,21 @@ pub fn start_vote(ctx: Context<StartVote>, voting_ends_ts: i64) -> Result<()> {
 }
 
 /// Cast a vote to either launch or refund.
-/// Weight is equal to the user's public contribution lamports.
-pub fn cast_vote(ctx: Context<CastVote>, support_launch: bool) -> Result<()> {
+/// Weight comes from the voter's public and/or VIP contribution (see VoteConfig::vote_weight);
+/// the raw lamports voting count towards turnout for the quorum.
+/// The ballot is recorded in a VoteReceipt for the current round. Voting again before
+/// voting_ends_ts changes the vote: the receipt's weight moves from its old side to the new one.
+/// A delegate also votes for the positions delegated to them, passed as remaining accounts
+/// (see cast_delegated_votes); with delegated positions the delegate needs no stake of their own.
+pub fn cast_vote<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>,
+    support_launch: bool,
+) -> Result<()> {
+    ctx.accounts.validate()?;
+
     let presale = &mut ctx.accounts.presale;
-    let user_position = &mut ctx.accounts.user_position;
 
-    require!(presale.phase == phase::VOTING, PresaleError::PresaleNotActive);
+    require!(presale.phase == PresalePhase::Voting, PresaleError::PresaleNotActive);
 
     let clock = Clock::get()?;
     require!(
@@ -54,85 +73,254 @@ pub fn cast_vote(ctx: Context<CastVote>, support_launch: bool) -> Result<()> {
         PresaleError::PresaleNotActive
     );
 
-    // Ensure the signer matches the recorded user
-    require_keys_eq!(
-        user_position.user,
-        ctx.accounts.voter.key(),
-        PresaleError::Unauthorized
+    let public_lamports = ctx
+        .accounts
+        .user_position
+        .as_ref()
+        .map_or(0, |position| position.public_contribution_lamports);
+    let vip_lamports = presale.vote_config.voting_vip_lamports(
+        ctx.accounts
+            .vip_position
+            .as_ref()
+            .map_or(0, |position| position.contribution_lamports),
+    );
+    let stake = public_lamports
+        .checked_add(vip_lamports)
+        .ok_or(PresaleError::MathOverflow)?;
+    let weight = presale.vote_config.vote_weight(public_lamports, vip_lamports)?;
+    // A delegate may have no stake of their own and only vote for the positions delegated to them
+    require!(
+        weight > 0 || !ctx.remaining_accounts.is_empty(),
+        PresaleError::NoVotingWeight
     );
 
-    // Prevent double-voting
-    require!(!user_position.has_voted, PresaleError::Unauthorized);
+    let receipt = &mut ctx.accounts.vote_receipt;
+    // A receipt of this round that already names the voter means the vote is being changed
+    // (or overrides the ballot a delegate cast for this position)
+    if receipt.voter != Pubkey::default() {
+        presale.remove_vote(receipt.support_launch, receipt.weight, receipt.stake_lamports)?;
+    }
+    presale.add_vote(support_launch, weight, stake)?;
+
+    receipt.presale = presale.key();
+    receipt.voter = ctx.accounts.voter.key();
+    receipt.cast_by = ctx.accounts.voter.key();
+    receipt.round = presale.vote_round;
+    receipt.support_launch = support_launch;
+    receipt.weight = weight;
+    receipt.stake_lamports = stake;
+    receipt.bump = ctx.bumps.vote_receipt;
+
+    cast_delegated_votes(
+        presale,
+        &ctx.accounts.voter.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        ctx.remaining_accounts,
+        support_launch,
+    )
+}
 
-    let weight = user_position.public_contribution_lamports;
-    require!(weight > 0, PresaleError::NothingToClaim);
+/// Vote on behalf of the positions delegated to `voter`.
+/// `delegated` holds (delegator UserPosition, delegator VipPosition, delegator VoteReceipt)
+/// triples; the VipPosition slot takes this program's id when the delegator has none.
+/// The delegator's receipt for this round is created (paid by the delegate) or updated with
+/// cast_by = delegate. A receipt the delegator cast themselves is left alone: their own vote
+/// overrides the delegate.
+// usize::is_multiple_of needs Rust 1.87, newer than some SBF platform-tools toolchains
+#[allow(clippy::manual_is_multiple_of)]
+fn cast_delegated_votes<'info>(
+    presale: &mut Account<'info, Presale>,
+    voter: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    delegated: &'info [AccountInfo<'info>],
+    support_launch: bool,
+) -> Result<()> {
+    require!(delegated.len() % 3 == 0, PresaleError::InvalidDelegation);
+    if delegated.is_empty() {
+        return Ok(());
+    }
 
-    if support_launch {
-        presale.vote_yes_weight = presale
-            .vote_yes_weight
-            .checked_add(weight)
-            .ok_or(PresaleError::HardCapExceeded)?;
-    } else {
-        presale.vote_no_weight = presale
-            .vote_no_weight
-            .checked_add(weight)
-            .ok_or(PresaleError::HardCapExceeded)?;
+    let presale_key = presale.key();
+    let round_bytes = presale.vote_round.to_le_bytes();
+    let (vip_pool_key, _) =
+        Pubkey::find_program_address(&[b"vip_pool", presale_key.as_ref()], &crate::ID);
+    for accounts in delegated.chunks(3) {
+        let position = Account::<UserPosition>::try_from(&accounts[0])?;
+        require_keys_eq!(position.presale, presale_key, PresaleError::InvalidDelegation);
+        require_keys_eq!(position.delegate, voter.key(), PresaleError::InvalidDelegation);
+
+        let vip_lamports = if accounts[1].key() == crate::ID {
+            0
+        } else {
+            let vip_position = Account::<VipPosition>::try_from(&accounts[1])?;
+            require_keys_eq!(vip_position.vip_pool, vip_pool_key, PresaleError::InvalidDelegation);
+            require_keys_eq!(vip_position.user, position.user, PresaleError::InvalidDelegation);
+            presale
+                .vote_config
+                .voting_vip_lamports(vip_position.contribution_lamports)
+        };
+
+        let receipt_info = &accounts[2];
+        let (receipt_key, bump) = Pubkey::find_program_address(
+            &[b"vote", presale_key.as_ref(), &round_bytes, position.user.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(receipt_info.key(), receipt_key, PresaleError::InvalidDelegation);
+
+        if receipt_info.data_is_empty() {
+            let space = 8 + VoteReceipt::LEN;
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    CreateAccount {
+                        from: voter.clone(),
+                        to: receipt_info.clone(),
+                    },
+                    &[&[
+                        b"vote",
+                        presale_key.as_ref(),
+                        &round_bytes,
+                        position.user.as_ref(),
+                        &[bump],
+                    ]],
+                ),
+                Rent::get()?.minimum_balance(space),
+                space as u64,
+                &crate::ID,
+            )?;
+        } else {
+            let previous = Account::<VoteReceipt>::try_from(receipt_info)?;
+            if previous.cast_by != voter.key() {
+                continue;
+            }
+            presale.remove_vote(
+                previous.support_launch,
+                previous.weight,
+                previous.stake_lamports,
+            )?;
+        }
+
+        let public_lamports = position.public_contribution_lamports;
+        let stake = public_lamports
+            .checked_add(vip_lamports)
+            .ok_or(PresaleError::MathOverflow)?;
+        let weight = presale.vote_config.vote_weight(public_lamports, vip_lamports)?;
+        presale.add_vote(support_launch, weight, stake)?;
+
+        let receipt = VoteReceipt {
+            presale: presale_key,
+            voter: position.user,
+            cast_by: voter.key(),
+            round: presale.vote_round,
+            support_launch,
+            weight,
+            stake_lamports: stake,
+            bump,
+        };
+        receipt.try_serialize(&mut &mut receipt_info.try_borrow_mut_data()?[..])?;
     }
 
-    user_position.has_voted = true;
+    Ok(())
+}
+
+/// Delegate this position's vote to `delegate` (Pubkey::default() removes the delegation).
+/// The delegate's cast_vote then votes for the position until the user votes themselves;
+/// a ballot already cast by the previous delegate stays until the user overrides it.
+pub fn delegate_vote(ctx: Context<DelegateVote>, delegate: Pubkey) -> Result<()> {
+    let user_position = &mut ctx.accounts.user_position;
+    require_keys_eq!(
+        user_position.user,
+        ctx.accounts.user.key(),
+        PresaleError::Unauthorized
+    );
+    require_keys_neq!(delegate, user_position.user, PresaleError::InvalidDelegation);
+
+    user_position.delegate = delegate;
+
+    emit!(VoteDelegated {
+        presale: ctx.accounts.presale.key(),
+        user: user_position.user,
+        delegate,
+    });
 
     Ok(())
 }
 
 /// Resolve the vote after voting_ends_ts has passed.
-/// If yes > no → Launchable; otherwise → Refundable.
+/// Without quorum the presale takes vote_config.default_outcome; with quorum an approved
+/// vote (see VoteConfig::approved) → Launchable, otherwise → Refundable.
 pub fn resolve_vote(ctx: Context<ResolveVote>) -> Result<()> {
+    let presale_key = ctx.accounts.presale.key();
     let presale = &mut ctx.accounts.presale;
     let clock = Clock::get()?;
 
-    require!(presale.phase == phase::VOTING, PresaleError::PresaleNotActive);
+    require!(presale.phase == PresalePhase::Voting, PresaleError::PresaleNotActive);
     require!(
         clock.unix_timestamp >= presale.voting_ends_ts,
         PresaleError::PresaleNotActive
     );
 
-    if presale.vote_yes_weight > presale.vote_no_weight {
+    let vote_config = presale.vote_config;
+    let total_raised = presale.total_raised_lamports()?;
+    let quorum_reached = vote_config.quorum_reached(presale.vote_turnout_lamports, total_raised);
+    let launch = if quorum_reached {
+        vote_config.approved(presale.vote_yes_weight, presale.vote_no_weight)
+    } else {
+        vote_config.default_outcome == outcome::LAUNCH
+    };
+
+    if launch {
         presale.outcome = outcome::LAUNCH;
-        presale.phase = phase::LAUNCHABLE;
-        // Give creator 24h after max(tge_ts, now) to launch
+        presale.transition_to(presale_key, PresalePhase::Launchable)?;
+        // Give creator 24h after max(tge_ts, now) to launch (no grace with time checks disabled)
         let base_ts = if clock.unix_timestamp > presale.tge_ts {
             clock.unix_timestamp
         } else {
             presale.tge_ts
         };
-        presale.launch_deadline_ts = base_ts + 24 * 60 * 60;
+        let grace_seconds = if presale.time_checks_enabled() { 24 * 60 * 60 } else { 0 };
+        presale.launch_deadline_ts = base_ts + grace_seconds;
         presale.refund_enabled = false;
     } else {
         presale.outcome = outcome::REFUND;
-        presale.phase = phase::REFUNDABLE;
+        presale.transition_to(presale_key, PresalePhase::Refundable)?;
         presale.refund_enabled = true;
     }
 
+    emit!(VoteResolved {
+        presale: presale_key,
+        yes_weight: presale.vote_yes_weight,
+        no_weight: presale.vote_no_weight,
+        turnout_lamports: presale.vote_turnout_lamports,
+        total_raised_lamports: total_raised,
+        quorum_bps: vote_config.quorum_bps,
+        approval_bps: vote_config.approval_bps,
+        quorum_reached,
+        outcome: presale.outcome,
+    });
+
     Ok(())
 }
 
 /// If a presale is launchable but the creator failed to launch before the deadline,
-/// enable refunds.
+/// enable refunds and slash the creator bond to contributors.
 pub fn enable_refunds_if_deadline_passed(
     ctx: Context<EnableRefundsIfDeadlinePassed>,
 ) -> Result<()> {
+    let presale_key = ctx.accounts.presale.key();
     let presale = &mut ctx.accounts.presale;
     let clock = Clock::get()?;
 
-    require!(presale.phase == phase::LAUNCHABLE, PresaleError::PresaleNotActive);
+    require!(presale.phase == PresalePhase::Launchable, PresaleError::PresaleNotActive);
     require!(
         clock.unix_timestamp > presale.launch_deadline_ts,
         PresaleError::PresaleNotActive
     );
 
-    presale.phase = phase::REFUNDABLE;
+    presale.transition_to(presale_key, PresalePhase::Refundable)?;
     presale.refund_enabled = true;
     presale.outcome = outcome::REFUND;
+    presale.slash_creator_bond(presale_key);
 
     Ok(())
 }
@@ -146,7 +334,7 @@ pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
 
     require!(presale.refund_enabled, PresaleError::PresaleNotMigrated);
     require!(
-        presale.phase == phase::REFUNDABLE,
+        presale.phase == PresalePhase::Refundable,
         PresaleError::PresaleNotMigrated
     );
 
@@ -159,24 +347,43 @@ pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
 
     require!(!user_position.refunded, PresaleError::NothingToClaim);
 
-    let amount = user_position.public_contribution_lamports;
-    require!(amount > 0, PresaleError::NothingToClaim);
-
-    // Transfer lamports from vault back to user
-    **public_sol_vault.to_account_info().try_borrow_mut_lamports()? = public_sol_vault
-        .to_account_info()
-        .lamports()
-        .checked_sub(amount)
-        .ok_or(PresaleError::InsufficientFunds)?;
+    // Only the part of the contribution still in escrow comes back, plus the slashed creator
+    // bond share (all a contributor gets when the creator withdrew everything and never launched)
+    let amount = presale.refund_amount(user_position.public_contribution_lamports)?;
+    let bond_share = presale.bond_share(user_position.public_contribution_lamports)?;
+    require!(amount > 0 || bond_share > 0, PresaleError::NothingToClaim);
+
+    if amount > 0 {
+        // Transfer lamports from vault back to user
+        **public_sol_vault.to_account_info().try_borrow_mut_lamports()? = public_sol_vault
+            .to_account_info()
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(PresaleError::InsufficientFunds)?;
+
+        **user.to_account_info().try_borrow_mut_lamports()? = user
+            .to_account_info()
+            .lamports()
+            .checked_add(amount)
+            .ok_or(PresaleError::InsufficientFunds)?;
+    }
 
-    **user.to_account_info().try_borrow_mut_lamports()? = user
-        .to_account_info()
-        .lamports()
-        .checked_add(amount)
-        .ok_or(PresaleError::InsufficientFunds)?;
+    // Slashed creator bond share, paid from creator_bond
+    if bond_share > 0 {
+        transfer_lamports(
+            &ctx.accounts.creator_bond.to_account_info(),
+            &user.to_account_info(),
+            bond_share,
+        )?;
+    }
 
     user_position.refunded = true;
 
+    // Refunded tokens no longer count against the global public cap
+    presale.total_tokens_allocated = presale
+        .total_tokens_allocated
+        .saturating_sub(user_position.tokens_allocated);
+
     Ok(())
 }
 
@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
 use crate::state::accounts::WhitelistUser;
+use crate::state::data::MAX_WHITELIST_TIERS;
+use crate::errors::PresaleError;
 
 /// Whitelist a user for a presale
 /// Admin-only
+/// Tier 1..=MAX_WHITELIST_TIERS (tier 0 revokes the entry)
 /// Creates/updates WhitelistEntry PDA
 /// PDA seeds: ["whitelist", presale_pubkey, user_pubkey]
 pub fn whitelist_user(
@@ -11,6 +14,7 @@ pub fn whitelist_user(
     max_contribution_lamports: u64,
 ) -> Result<()> {
     ctx.accounts.validate()?;
+    require!(tier as usize <= MAX_WHITELIST_TIERS, PresaleError::InvalidTier);
 
     let whitelist = &mut ctx.accounts.whitelist;
     whitelist.presale = ctx.accounts.presale.key();
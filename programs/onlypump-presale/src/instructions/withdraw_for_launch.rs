@@ -1,11 +1,18 @@
 use anchor_lang::prelude::*;
 use crate::state::accounts::WithdrawForLaunch;
 use crate::errors::PresaleError;
+use crate::events::EscrowReleased;
+use crate::utils::{apply_bps, collect_fee, drain_vault, transfer_lamports, BPS_DENOMINATOR};
 
 /// Withdraw collected SOL from presale to authority for launching token
 /// Authority-only (presale.authority)
-/// Transfers all SOL from public_sol_vault to authority
-/// Can only be called after presale voting is complete and outcome is Launch
+/// Without escrow: merges the VIP raise (vip_sol_vault) into public_sol_vault and pays out
+/// all of it. With escrow: pays out only escrow.launch_tranche_bps of each vault's raise and
+/// starts the escrow deadline; the rest waits for release_escrow.
+/// Either way the platform fee (platform.fee_bps) is skimmed into fee_vault first.
+/// Can only be called once, after presale voting is complete and outcome is Launch,
+/// before token funding is sealed and before launch_deadline_ts (after that the presale
+/// belongs to enable_refunds_if_deadline_passed)
 pub fn withdraw_for_launch(ctx: Context<WithdrawForLaunch>) -> Result<()> {
     ctx.accounts.validate()?;
 
@@ -13,24 +20,87 @@ pub fn withdraw_for_launch(ctx: Context<WithdrawForLaunch>) -> Result<()> {
 
     // Verify presale state allows withdrawal
     require!(presale.is_finalized, PresaleError::PresaleNotFinalized);
+    require!(!presale.funding_sealed, PresaleError::FundingSealed);
     require!(
         presale.outcome == crate::instructions::vote::outcome::LAUNCH,
         PresaleError::Unauthorized
     );
+    require!(
+        Clock::get()?.unix_timestamp <= presale.launch_deadline_ts,
+        PresaleError::LaunchDeadlinePassed
+    );
+    require!(
+        presale.escrow_released_bps == 0,
+        PresaleError::LaunchFundsAlreadyWithdrawn
+    );
+
+    let public_sol_vault = ctx.accounts.public_sol_vault.to_account_info();
+    let vip_sol_vault = ctx.accounts.vip_sol_vault.to_account_info();
+
+    let (gross, released_bps) = if presale.escrow.is_enabled() {
+        let tranche_bps = presale.escrow.launch_tranche_bps;
+        let vip_tranche = if vip_sol_vault.owner == &crate::ID {
+            apply_bps(presale.vip_raised_lamports, tranche_bps)?
+        } else {
+            0
+        };
+        transfer_lamports(&vip_sol_vault, &public_sol_vault, vip_tranche)?;
+        let public_tranche = apply_bps(presale.public_raised_lamports, tranche_bps)?;
+        let gross = public_tranche
+            .checked_add(vip_tranche)
+            .ok_or(PresaleError::MathOverflow)?;
+        (gross, tranche_bps)
+    } else {
+        drain_vault(&vip_sol_vault, &public_sol_vault)?;
+        // Get balance from public_sol_vault (public + VIP raise)
+        (public_sol_vault.lamports(), BPS_DENOMINATOR as u16)
+    };
 
-    // Get balance from public_sol_vault
-    let vault_balance = ctx.accounts.public_sol_vault.lamports();
-    
-    if vault_balance == 0 {
+    if gross == 0 {
         return Err(PresaleError::InsufficientFunds.into());
     }
 
-    // Transfer all SOL from vault to authority
-    **ctx.accounts.public_sol_vault.try_borrow_mut_lamports()? -= vault_balance;
-    **ctx.accounts.authority.try_borrow_mut_lamports()? += vault_balance;
+    let fee = collect_fee(
+        presale.key(),
+        &public_sol_vault,
+        &ctx.accounts.fee_vault.to_account_info(),
+        gross,
+        ctx.accounts.platform.fee_bps,
+    )?;
 
-    msg!("Withdrawn {} lamports from presale to authority", vault_balance);
+    // Transfer the remaining SOL from vault to authority
+    let payout = gross
+        .checked_sub(fee)
+        .ok_or(PresaleError::MathOverflow)?;
+    transfer_lamports(
+        &public_sol_vault,
+        &ctx.accounts.authority.to_account_info(),
+        payout,
+    )?;
+
+    let presale_key = ctx.accounts.presale.key();
+    let presale = &mut ctx.accounts.presale;
+    presale.escrow_released_bps = released_bps;
+    if presale.escrow.is_enabled() {
+        presale.escrow_deadline_ts = Clock::get()?
+            .unix_timestamp
+            .checked_add(presale.escrow.release_delay_seconds)
+            .ok_or(PresaleError::MathOverflow)?;
+    }
+
+    emit!(EscrowReleased {
+        presale: presale_key,
+        authority: presale.authority,
+        amount_lamports: payout,
+        platform_fee: fee,
+        released_bps,
+    });
+
+    msg!(
+        "Withdrawn {} lamports from presale to authority ({} lamports platform fee)",
+        payout,
+        fee
+    );
 
     Ok(())
 }
-
@@ -1,12 +1,16 @@
 use anchor_lang::prelude::*;
 
+pub mod adapters;
+pub mod allowlist;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod revenue;
 pub mod state;
 pub mod utils;
 
 use state::accounts::*;
+use state::data::*;
 
 // NOTE: This must match the program ID used when deploying (see Anchor.toml)
 declare_id!("5zqdoDng2LnQ7JbiemiRwzTaPnnEU4eMXMfCCF3P4xQQ");
@@ -25,25 +29,37 @@ pub mod onlypump_presale {
         instructions::initialize_platform::initialize_platform(ctx, operator, treasury, fee_bps)
     }
 
+    /// Sweep accrued platform fees from the fee vault to the treasury
+    pub fn sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
+        instructions::sweep_fees::sweep_fees(ctx)
+    }
+
+    /// Update operator, treasury and/or fee (owner-only)
+    pub fn update_platform_config(
+        ctx: Context<UpdatePlatformConfig>,
+        params: UpdatePlatformConfigParams,
+    ) -> Result<()> {
+        instructions::update_platform_config::update_platform_config(ctx, params)
+    }
+
+    /// Propose a new platform owner (owner-only, first step of the transfer)
+    pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+        instructions::transfer_ownership::propose_owner(ctx, new_owner)
+    }
+
+    /// Accept ownership of the platform (pending owner, second step of the transfer)
+    pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+        instructions::transfer_ownership::accept_owner(ctx)
+    }
+
     /// Create a new presale for a token (token doesn't need to exist yet)
     pub fn create_presale(
         ctx: Context<CreatePresale>,
         mint: Pubkey,
         authority: Pubkey,
-        public_start_ts: i64,
-        public_end_ts: i64,
-        public_price_lamports_per_token: u64,
-        hard_cap_lamports: u64,
+        params: CreatePresaleParams,
     ) -> Result<()> {
-        instructions::create_presale::create_presale(
-            ctx,
-            mint,
-            authority,
-            public_start_ts,
-            public_end_ts,
-            public_price_lamports_per_token,
-            hard_cap_lamports,
-        )
+        instructions::create_presale::create_presale(ctx, mint, authority, params)
     }
 
     /// Initialize token vaults for a presale (call after token is created)
@@ -51,16 +67,50 @@ pub mod onlypump_presale {
         instructions::initialize_vaults::initialize_vaults(ctx)
     }
 
-    /// Fund the presale token vault with the required tokens (800M total)
+    /// Deposit tokens into the presale token vault (until funding is sealed)
     pub fn fund_presale_tokens(ctx: Context<FundPresaleTokens>, amount: u64) -> Result<()> {
         instructions::fund_presale_tokens::fund_presale_tokens(ctx, amount)
     }
 
-    /// Withdraw collected SOL from presale to authority for token launch
+    /// Record the final public/LP/ecosystem token amounts and lock funding
+    pub fn seal_funding(
+        ctx: Context<SealFunding>,
+        public_tokens: u64,
+        lp_tokens: u64,
+        ecosystem_tokens: u64,
+    ) -> Result<()> {
+        instructions::seal_funding::seal_funding(ctx, public_tokens, lp_tokens, ecosystem_tokens)
+    }
+
+    /// Withdraw collected SOL (minus platform fee) from presale to authority for token launch;
+    /// only the launch tranche when the presale uses escrow
     pub fn withdraw_for_launch(ctx: Context<WithdrawForLaunch>) -> Result<()> {
         instructions::withdraw_for_launch::withdraw_for_launch(ctx)
     }
 
+    /// Release the escrowed rest of the raise once the token vault is funded and sealed
+    pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+        instructions::escrow::release_escrow(ctx)
+    }
+
+    /// Open refunds of the escrow remainder when funding was not sealed by the escrow deadline
+    pub fn enable_escrow_refunds(ctx: Context<EnableEscrowRefunds>) -> Result<()> {
+        instructions::escrow::enable_escrow_refunds(ctx)
+    }
+
+    /// Return the creator bond to the authority after launch (or a no-fault refund)
+    pub fn return_creator_bond(ctx: Context<ReturnCreatorBond>) -> Result<()> {
+        instructions::creator_bond::return_creator_bond(ctx)
+    }
+
+    /// Create the token on pump.fun and buy into token_vault with the raised SOL
+    pub fn launch_on_pumpfun(
+        ctx: Context<LaunchOnPumpfun>,
+        params: LaunchOnPumpfunParams,
+    ) -> Result<()> {
+        instructions::launch_on_pumpfun::launch_on_pumpfun(ctx, params)
+    }
+
     /// Whitelist a user for a presale
     pub fn whitelist_user(
         ctx: Context<WhitelistUser>,
@@ -70,39 +120,126 @@ pub mod onlypump_presale {
         instructions::whitelist_user::whitelist_user(ctx, tier, max_contribution_lamports)
     }
 
-    /// User contributes SOL to the public presale
-    pub fn contribute_public(ctx: Context<ContributePublic>, amount_lamports: u64) -> Result<()> {
-        instructions::contribute_public::contribute_public(ctx, amount_lamports)
+    /// Set the Merkle allowlist root for a whitelist tier
+    pub fn set_whitelist_root(
+        ctx: Context<SetWhitelistRoot>,
+        tier: u8,
+        root: [u8; 32],
+    ) -> Result<()> {
+        instructions::set_whitelist_root::set_whitelist_root(ctx, tier, root)
+    }
+
+    /// User contributes SOL to the public presale (optionally accepting a partial fill at the cap)
+    pub fn contribute_public(
+        ctx: Context<ContributePublic>,
+        amount_lamports: u64,
+        allow_partial_fill: bool,
+        allowlist_proof: Option<AllowlistProof>,
+    ) -> Result<()> {
+        instructions::contribute_public::contribute_public(
+            ctx,
+            amount_lamports,
+            allow_partial_fill,
+            allowlist_proof,
+        )
+    }
+
+    /// Open the VIP round (own SOL vault, price and hard cap) for a presale
+    pub fn create_vip_pool(
+        ctx: Context<CreateVipPool>,
+        price_lamports_per_token: u64,
+        hard_cap_lamports: u64,
+    ) -> Result<()> {
+        instructions::vip_pool::create_vip_pool(ctx, price_lamports_per_token, hard_cap_lamports)
     }
 
-    /// Finalize the presale
+    /// Whitelisted user contributes SOL to the VIP round
+    pub fn contribute_vip(
+        ctx: Context<ContributeVip>,
+        amount_lamports: u64,
+        allowlist_proof: Option<AllowlistProof>,
+    ) -> Result<()> {
+        instructions::vip_pool::contribute_vip(ctx, amount_lamports, allowlist_proof)
+    }
+
+    /// Configure how the migration payout is split between creator, platform and VIP holders
+    pub fn configure_influencer(
+        ctx: Context<ConfigureInfluencer>,
+        creator: Pubkey,
+        creator_share_bps: u16,
+        vip_share_from_creator_bps: u16,
+        vip_share_from_platform_bps: u16,
+    ) -> Result<()> {
+        instructions::configure_influencer::configure_influencer(
+            ctx,
+            creator,
+            creator_share_bps,
+            vip_share_from_creator_bps,
+            vip_share_from_platform_bps,
+        )
+    }
+
+    /// Finalize the presale (permissionless after the end time; refundable if soft cap missed)
     pub fn finalize_presale(ctx: Context<FinalizePresale>) -> Result<()> {
         instructions::finalize_presale::finalize_presale(ctx)
     }
 
-    /// Migrate presale and create LP
-    pub fn migrate_and_create_lp(
-        ctx: Context<MigrateAndCreateLp>,
-        lp_sol_amount: u64,
+    /// Migrate presale and create the LP pool on the chosen AMM
+    pub fn migrate_and_create_lp<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateAndCreateLp<'info>>,
+        params: MigrateAndCreateLpParams,
     ) -> Result<()> {
-        instructions::migrate_and_create_lp::migrate_and_create_lp(ctx, lp_sol_amount)
+        instructions::migrate_and_create_lp::migrate_and_create_lp(ctx, params)
     }
 
-    /// User claims their allocated tokens after migration
+    /// Release the locked LP tokens to the configured recipient once the lock expires
+    pub fn unlock_lp(ctx: Context<UnlockLp>) -> Result<()> {
+        instructions::lp_lock::unlock_lp(ctx)
+    }
+
+    /// Burn the locked LP tokens (permanent liquidity)
+    pub fn burn_lp(ctx: Context<BurnLp>) -> Result<()> {
+        instructions::lp_lock::burn_lp(ctx)
+    }
+
+    /// User claims their vested tokens after migration
     pub fn claim_tokens(ctx: Context<ClaimTokens>) -> Result<()> {
         instructions::claim_tokens::claim_tokens(ctx)
     }
 
+    /// Read-only: vested tokens of a position at `timestamp`
+    pub fn get_vested_amount(ctx: Context<GetVestedAmount>, timestamp: i64) -> Result<u64> {
+        instructions::get_vested_amount::get_vested_amount(ctx, timestamp)
+    }
+
+    /// VIP contributor claims their allocated tokens after migration
+    pub fn claim_vip_tokens(ctx: Context<ClaimVipTokens>) -> Result<()> {
+        instructions::vip_pool::claim_vip_tokens(ctx)
+    }
+
+    /// VIP contributor claims their pro-rata share of the VIP revenue share
+    pub fn claim_vip_rewards(ctx: Context<ClaimVipRewards>) -> Result<()> {
+        instructions::vip_pool::claim_vip_rewards(ctx)
+    }
+
     /// Start a community vote for a presale (admin-triggered)
     pub fn start_vote(ctx: Context<StartVote>, voting_ends_ts: i64) -> Result<()> {
         instructions::vote::start_vote(ctx, voting_ends_ts)
     }
 
-    /// Cast a vote to either launch or refund (stake-weighted)
-    pub fn cast_vote(ctx: Context<CastVote>, support_launch: bool) -> Result<()> {
+    /// Cast a vote to either launch or refund (stake-weighted), also for delegated positions
+    pub fn cast_vote<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>,
+        support_launch: bool,
+    ) -> Result<()> {
         instructions::vote::cast_vote(ctx, support_launch)
     }
 
+    /// Delegate a position's vote to another wallet
+    pub fn delegate_vote(ctx: Context<DelegateVote>, delegate: Pubkey) -> Result<()> {
+        instructions::vote::delegate_vote(ctx, delegate)
+    }
+
     /// Resolve an ended vote, setting presale phase to Launchable or Refundable
     pub fn resolve_vote(ctx: Context<ResolveVote>) -> Result<()> {
         instructions::vote::resolve_vote(ctx)
@@ -119,4 +256,9 @@ pub mod onlypump_presale {
     pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
         instructions::vote::claim_refund(ctx)
     }
+
+    /// Allow a VIP contributor to reclaim their SOL when refunds are enabled
+    pub fn claim_vip_refund(ctx: Context<ClaimVipRefund>) -> Result<()> {
+        instructions::vip_pool::claim_vip_refund(ctx)
+    }
 }
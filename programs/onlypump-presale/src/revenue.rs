@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::state::data::InfluencerConfig;
+use crate::errors::PresaleError;
+use crate::utils::apply_bps;
+
+/// Where the SOL paid out at migration ends up
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RevenueSplit {
+    pub creator_lamports: u64,
+    pub platform_lamports: u64,
+    pub vip_lamports: u64,
+}
+
+/// Split `payout` according to the influencer config.
+/// Without a config (or with `vip_enabled == false`, i.e. no VIP contributions to
+/// reward) the corresponding shares stay with the platform / creator.
+pub fn split_revenue(
+    config: Option<&InfluencerConfig>,
+    payout: u64,
+    vip_enabled: bool,
+) -> Result<RevenueSplit> {
+    let Some(config) = config else {
+        return Ok(RevenueSplit {
+            platform_lamports: payout,
+            ..RevenueSplit::default()
+        });
+    };
+
+    let creator_gross = apply_bps(payout, config.creator_share_bps)?;
+    let platform_gross = payout
+        .checked_sub(creator_gross)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    let (vip_from_creator, vip_from_platform) = if vip_enabled {
+        (
+            apply_bps(creator_gross, config.vip_share_from_creator_bps)?,
+            apply_bps(platform_gross, config.vip_share_from_platform_bps)?,
+        )
+    } else {
+        (0, 0)
+    };
+
+    Ok(RevenueSplit {
+        creator_lamports: creator_gross - vip_from_creator,
+        platform_lamports: platform_gross - vip_from_platform,
+        vip_lamports: vip_from_creator + vip_from_platform,
+    })
+}
@@ -1,9 +1,13 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    token::{Token, TokenAccount, Mint},
+    associated_token::AssociatedToken,
+    token::{spl_token, Token, TokenAccount, Mint},
+    token_interface::{
+        Mint as InterfaceMint, TokenAccount as InterfaceTokenAccount, TokenInterface,
+    },
 };
 use crate::state::data::*;
-use crate::utils::assert_admin;
+use crate::utils::{assert_admin, assert_owner};
 
 // ========== Account Structures ==========
 
@@ -17,11 +21,112 @@ pub struct InitializePlatform<'info> {
         bump
     )]
     pub platform: Account<'info, PlatformConfig>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    /// CHECK: Platform fee vault PDA (regular account, holds skimmed SOL fees)
+    pub fee_vault: UncheckedAccount<'info>,
     #[account(mut)]
     pub owner: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SweepFees<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    /// CHECK: Platform fee vault PDA
+    pub fee_vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: Treasury account (must match platform.treasury)
+    pub treasury: UncheckedAccount<'info>,
+    pub owner: Signer<'info>,
+}
+
+impl<'info> SweepFees<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_owner(&self.platform, &self.owner.key())?;
+        require!(
+            self.treasury.key() == self.platform.treasury,
+            crate::errors::PresaleError::Unauthorized
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdatePlatformConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    pub owner: Signer<'info>,
+}
+
+impl<'info> UpdatePlatformConfig<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_owner(&self.platform, &self.owner.key())?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    pub owner: Signer<'info>,
+}
+
+impl<'info> ProposeOwner<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_owner(&self.platform, &self.owner.key())?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    pub new_owner: Signer<'info>,
+}
+
+impl<'info> AcceptOwner<'info> {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.platform.pending_owner != Pubkey::default(),
+            crate::errors::PresaleError::NoPendingOwner
+        );
+        require!(
+            self.new_owner.key() == self.platform.pending_owner,
+            crate::errors::PresaleError::Unauthorized
+        );
+        Ok(())
+    }
+}
+
 #[derive(Accounts)]
 pub struct CreatePresale<'info> {
     #[account(
@@ -46,29 +151,535 @@ pub struct CreatePresale<'info> {
     )]
     /// CHECK: Public SOL vault PDA (regular account, not token account)
     pub public_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8,
+        seeds = [b"creator_bond", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Creator bond PDA (holds params.creator_bond_lamports)
+    pub creator_bond: UncheckedAccount<'info>,
     #[account(mut)]
     pub admin: Signer<'info>,
+    /// Presale authority posting the bond; required when params.creator_bond_lamports > 0
+    #[account(mut)]
+    pub creator: Option<Signer<'info>>,
     /// CHECK: Token mint pubkey (token doesn't need to exist yet)
     pub mint_pubkey: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct InitializeVaults<'info> {
+pub struct InitializeVaults<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    /// Already exists if launch_on_pumpfun bought into it
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"token_vault", presale.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = token_vault_authority,
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"token_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Token vault authority PDA
+    pub token_vault_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"ecosystem_vault", presale.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = ecosystem_vault_authority,
+    )]
+    pub ecosystem_vault: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"ecosystem_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Ecosystem vault authority PDA
+    pub ecosystem_vault_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CreatePresale<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_admin(&self.platform, &self.admin.key())?;
+        Ok(())
+    }
+}
+
+impl<'info> InitializeVaults<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_admin(&self.platform, &self.admin.key())?;
+        require!(
+            self.mint.key() == self.presale.mint,
+            crate::errors::PresaleError::MintMismatch
+        );
+        require!(
+            self.mint.decimals == self.presale.token_decimals,
+            crate::errors::PresaleError::MintDecimalsMismatch
+        );
+        // In ProRata mode the launch decides how many tokens exist, so there is nothing to check yet
+        require!(
+            self.presale.claim_mode == ClaimMode::ProRata
+                || self.mint.supply >= self.presale.total_token_allocation()?,
+            crate::errors::PresaleError::InsufficientMintSupply
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawForLaunch<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        seeds = [b"public_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Public SOL vault PDA
+    pub public_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vip_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: VIP SOL vault PDA (may not exist if the presale has no VIP pool)
+    pub vip_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    /// CHECK: Platform fee vault PDA
+    pub fee_vault: UncheckedAccount<'info>,
+    /// CHECK: Authority who can withdraw (presale.authority)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawForLaunch<'info> {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.authority.key() == self.presale.authority,
+            crate::errors::PresaleError::Unauthorized
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ReleaseEscrow<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        seeds = [b"public_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Public SOL vault PDA
+    pub public_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vip_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: VIP SOL vault PDA (may not exist if the presale has no VIP pool)
+    pub vip_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    /// CHECK: Platform fee vault PDA
+    pub fee_vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ReleaseEscrow<'info> {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.authority.key() == self.presale.authority,
+            crate::errors::PresaleError::Unauthorized
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct EnableEscrowRefunds<'info> {
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+}
+
+#[derive(Accounts)]
+pub struct LaunchOnPumpfun<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        seeds = [b"public_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Public SOL vault PDA
+    pub public_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vip_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: VIP SOL vault PDA (may not exist if the presale has no VIP pool)
+    pub vip_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    /// CHECK: Platform fee vault PDA
+    pub fee_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"launch_buyer", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: System-owned PDA that creates and buys on pump.fun on the presale's behalf
+    pub launch_buyer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"token_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Token vault PDA, created here once the mint exists
+    pub token_vault: UncheckedAccount<'info>,
+    /// Reserved mint keypair (must be presale.mint)
+    #[account(mut)]
+    pub mint: Signer<'info>,
+    /// CHECK: pump.fun program (must be platform.pumpfun_program)
+    pub pumpfun_program: UncheckedAccount<'info>,
+    /// CHECK: pump.fun mint authority PDA
+    pub pumpfun_mint_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: pump.fun bonding curve PDA
+    pub bonding_curve: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: Bonding curve token account
+    pub associated_bonding_curve: UncheckedAccount<'info>,
+    /// CHECK: pump.fun global config
+    pub pumpfun_global: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: pump.fun fee recipient
+    pub pumpfun_fee_recipient: UncheckedAccount<'info>,
+    /// CHECK: Metaplex token metadata program
+    pub mpl_token_metadata: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: Metadata PDA for the mint
+    pub metadata: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: pump.fun creator fee vault
+    pub creator_vault: UncheckedAccount<'info>,
+    /// CHECK: pump.fun event authority
+    pub pumpfun_event_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> LaunchOnPumpfun<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_admin(&self.platform, &self.admin.key())?;
+        require!(
+            self.mint.key() == self.presale.mint,
+            crate::errors::PresaleError::MintMismatch
+        );
+        require!(
+            self.pumpfun_program.key() == self.platform.pumpfun_program,
+            crate::errors::PresaleError::Unauthorized
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct FundPresaleTokens<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        seeds = [b"token_vault", presale.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub from_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FundPresaleTokens<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_admin(&self.platform, &self.authority.key())?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SealFunding<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        seeds = [b"token_vault", presale.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SealFunding<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_admin(&self.platform, &self.admin.key())?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WhitelistUser<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + WhitelistEntry::LEN,
+        seeds = [b"whitelist", presale.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub whitelist: Account<'info, WhitelistEntry>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: User to whitelist
+    pub user: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WhitelistUser<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_admin(&self.platform, &self.admin.key())?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetWhitelistRoot<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetWhitelistRoot<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_admin(&self.platform, &self.admin.key())?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ContributePublic<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut, has_one = platform)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        seeds = [b"public_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Public SOL vault
+    pub public_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserPosition::LEN,
+        seeds = [b"position", presale.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Optional whitelist entry; required during the whitelist window
+    #[account(
+        seeds = [b"whitelist", presale.key().as_ref(), user.key().as_ref()],
+        bump = whitelist.bump
+    )]
+    pub whitelist: Option<Account<'info, WhitelistEntry>>,
+    /// CHECK: Instructions sysvar, read to find the Ed25519 voucher signature
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVipPool<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    pub presale: Account<'info, Presale>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + VipPool::LEN,
+        seeds = [b"vip_pool", presale.key().as_ref()],
+        bump
+    )]
+    pub vip_pool: Account<'info, VipPool>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8,
+        seeds = [b"vip_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: VIP SOL vault PDA (regular account, not token account)
+    pub vip_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8,
+        seeds = [b"vip_rewards_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: VIP rewards vault PDA (holds the VIP revenue share until claimed)
+    pub vip_rewards_vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateVipPool<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_admin(&self.platform, &self.admin.key())?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ContributeVip<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut, has_one = platform)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        seeds = [b"vip_pool", presale.key().as_ref()],
+        bump = vip_pool.bump
+    )]
+    pub vip_pool: Account<'info, VipPool>,
+    #[account(
+        mut,
+        seeds = [b"vip_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: VIP SOL vault
+    pub vip_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + VipPosition::LEN,
+        seeds = [b"vip_position", vip_pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub vip_position: Account<'info, VipPosition>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Optional whitelist entry; VIP contributions need a whitelist grant from it or a proof
+    #[account(
+        seeds = [b"whitelist", presale.key().as_ref(), user.key().as_ref()],
+        bump = whitelist.bump
+    )]
+    pub whitelist: Option<Account<'info, WhitelistEntry>>,
+    /// CHECK: Instructions sysvar, read to find the Ed25519 voucher signature
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVipTokens<'info> {
+    pub presale: Account<'info, Presale>,
     #[account(
-        seeds = [b"platform"],
-        bump = platform.bump
+        seeds = [b"vip_pool", presale.key().as_ref()],
+        bump = vip_pool.bump
     )]
-    pub platform: Account<'info, PlatformConfig>,
-    #[account(mut)]
-    pub presale: Account<'info, Presale>,
+    pub vip_pool: Account<'info, VipPool>,
     #[account(
-        init,
-        payer = admin,
+        mut,
         seeds = [b"token_vault", presale.key().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = token_vault_authority,
+        bump
     )]
     pub token_vault: Account<'info, TokenAccount>,
     #[account(
@@ -78,99 +689,80 @@ pub struct InitializeVaults<'info> {
     /// CHECK: Token vault authority PDA
     pub token_vault_authority: UncheckedAccount<'info>,
     #[account(
-        init,
-        payer = admin,
-        seeds = [b"ecosystem_vault", presale.key().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = ecosystem_vault_authority,
-    )]
-    pub ecosystem_vault: Account<'info, TokenAccount>,
-    #[account(
-        seeds = [b"ecosystem_vault", presale.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"vip_position", vip_pool.key().as_ref(), user.key().as_ref()],
+        bump = vip_position.bump
     )]
-    /// CHECK: Ecosystem vault authority PDA
-    pub ecosystem_vault_authority: UncheckedAccount<'info>,
+    pub vip_position: Account<'info, VipPosition>,
     #[account(mut)]
-    pub admin: Signer<'info>,
-    pub mint: Account<'info, Mint>,
-    pub system_program: Program<'info, System>,
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
-impl<'info> CreatePresale<'info> {
-    pub fn validate(&self) -> Result<()> {
-        assert_admin(&self.platform, &self.admin.key())?;
-        Ok(())
-    }
-}
-
-impl<'info> InitializeVaults<'info> {
-    pub fn validate(&self) -> Result<()> {
-        assert_admin(&self.platform, &self.admin.key())?;
-        Ok(())
-    }
-}
-
 #[derive(Accounts)]
-pub struct WithdrawForLaunch<'info> {
+pub struct ClaimVipRefund<'info> {
     #[account(mut)]
     pub presale: Account<'info, Presale>,
     #[account(
         mut,
-        seeds = [b"public_sol_vault", presale.key().as_ref()],
+        seeds = [b"vip_pool", presale.key().as_ref()],
+        bump = vip_pool.bump
+    )]
+    pub vip_pool: Account<'info, VipPool>,
+    #[account(
+        mut,
+        seeds = [b"vip_sol_vault", presale.key().as_ref()],
         bump
     )]
-    /// CHECK: Public SOL vault PDA
-    pub public_sol_vault: UncheckedAccount<'info>,
-    /// CHECK: Authority who can withdraw (presale.authority)
+    /// CHECK: VIP SOL vault holding VIP contributions
+    pub vip_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"creator_bond", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Creator bond PDA (pays the slashed bond share)
+    pub creator_bond: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vip_position", vip_pool.key().as_ref(), user.key().as_ref()],
+        bump = vip_position.bump
+    )]
+    pub vip_position: Account<'info, VipPosition>,
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
-impl<'info> WithdrawForLaunch<'info> {
-    pub fn validate(&self) -> Result<()> {
-        require!(
-            self.authority.key() == self.presale.authority,
-            crate::errors::PresaleError::Unauthorized
-        );
-        Ok(())
-    }
-}
-
 #[derive(Accounts)]
-pub struct FundPresaleTokens<'info> {
+pub struct ClaimVipRewards<'info> {
+    pub presale: Account<'info, Presale>,
     #[account(
-        seeds = [b"platform"],
-        bump = platform.bump
+        seeds = [b"vip_pool", presale.key().as_ref()],
+        bump = vip_pool.bump
     )]
-    pub platform: Account<'info, PlatformConfig>,
-    #[account(mut)]
-    pub presale: Account<'info, Presale>,
+    pub vip_pool: Account<'info, VipPool>,
     #[account(
         mut,
-        seeds = [b"token_vault", presale.key().as_ref()],
+        seeds = [b"vip_rewards_vault", presale.key().as_ref()],
         bump
     )]
-    pub token_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub from_token_account: Account<'info, TokenAccount>,
+    /// CHECK: VIP rewards vault PDA
+    pub vip_rewards_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vip_position", vip_pool.key().as_ref(), user.key().as_ref()],
+        bump = vip_position.bump
+    )]
+    pub vip_position: Account<'info, VipPosition>,
     #[account(mut)]
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-}
-
-impl<'info> FundPresaleTokens<'info> {
-    pub fn validate(&self) -> Result<()> {
-        assert_admin(&self.platform, &self.authority.key())?;
-        Ok(())
-    }
+    pub user: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct WhitelistUser<'info> {
+pub struct ConfigureInfluencer<'info> {
     #[account(
         seeds = [b"platform"],
         bump = platform.bump
@@ -181,51 +773,23 @@ pub struct WhitelistUser<'info> {
     #[account(
         init_if_needed,
         payer = admin,
-        space = 8 + WhitelistEntry::LEN,
-        seeds = [b"whitelist", presale.key().as_ref(), user.key().as_ref()],
+        space = 8 + InfluencerConfig::LEN,
+        seeds = [b"influencer", presale.key().as_ref()],
         bump
     )]
-    pub whitelist: Account<'info, WhitelistEntry>,
+    pub influencer_config: Account<'info, InfluencerConfig>,
     #[account(mut)]
     pub admin: Signer<'info>,
-    /// CHECK: User to whitelist
-    pub user: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 
-impl<'info> WhitelistUser<'info> {
+impl<'info> ConfigureInfluencer<'info> {
     pub fn validate(&self) -> Result<()> {
         assert_admin(&self.platform, &self.admin.key())?;
         Ok(())
     }
 }
 
-#[derive(Accounts)]
-pub struct ContributePublic<'info> {
-    #[account(mut)]
-    pub presale: Account<'info, Presale>,
-    #[account(
-        mut,
-        seeds = [b"public_sol_vault", presale.key().as_ref()],
-        bump
-    )]
-    /// CHECK: Public SOL vault
-    pub public_sol_vault: UncheckedAccount<'info>,
-    #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + UserPosition::LEN,
-        seeds = [b"position", presale.key().as_ref(), user.key().as_ref()],
-        bump
-    )]
-    pub user_position: Account<'info, UserPosition>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    /// CHECK: Optional whitelist entry
-    pub whitelist: Option<Account<'info, WhitelistEntry>>,
-    pub system_program: Program<'info, System>,
-}
-
 #[derive(Accounts)]
 pub struct StartVote<'info> {
     #[account(
@@ -250,14 +814,78 @@ impl<'info> StartVote<'info> {
 pub struct CastVote<'info> {
     #[account(mut)]
     pub presale: Account<'info, Presale>,
+    /// Public position of the voter (optional for VIP-only contributors and for
+    /// delegates voting only for others)
     #[account(
-        mut,
         seeds = [b"position", presale.key().as_ref(), voter.key().as_ref()],
         bump = user_position.bump
     )]
-    pub user_position: Account<'info, UserPosition>,
+    pub user_position: Option<Account<'info, UserPosition>>,
+    /// Required with vip_position
+    #[account(
+        seeds = [b"vip_pool", presale.key().as_ref()],
+        bump = vip_pool.bump
+    )]
+    pub vip_pool: Option<Account<'info, VipPool>>,
+    /// VIP position of the voter (optional for public-only contributors)
+    pub vip_position: Option<Account<'info, VipPosition>>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoteReceipt::LEN,
+        seeds = [
+            b"vote",
+            presale.key().as_ref(),
+            presale.vote_round.to_le_bytes().as_ref(),
+            voter.key().as_ref(),
+        ],
+        bump
+    )]
+    pub vote_receipt: Account<'info, VoteReceipt>,
     #[account(mut)]
     pub voter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CastVote<'info> {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(user_position) = &self.user_position {
+            require_keys_eq!(
+                user_position.user,
+                self.voter.key(),
+                crate::errors::PresaleError::Unauthorized
+            );
+        }
+        if let Some(vip_position) = &self.vip_position {
+            let vip_pool = self
+                .vip_pool
+                .as_ref()
+                .ok_or(crate::errors::PresaleError::InvalidPresaleConfig)?;
+            require_keys_eq!(
+                vip_position.vip_pool,
+                vip_pool.key(),
+                crate::errors::PresaleError::Unauthorized
+            );
+            require_keys_eq!(
+                vip_position.user,
+                self.voter.key(),
+                crate::errors::PresaleError::Unauthorized
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct DelegateVote<'info> {
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        seeds = [b"position", presale.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    pub user: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -266,6 +894,22 @@ pub struct ResolveVote<'info> {
     pub presale: Account<'info, Presale>,
 }
 
+#[derive(Accounts)]
+pub struct ReturnCreatorBond<'info> {
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        seeds = [b"creator_bond", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Creator bond PDA
+    pub creator_bond: UncheckedAccount<'info>,
+    #[account(mut, address = presale.authority)]
+    /// CHECK: Presale authority receiving the bond back
+    pub authority: UncheckedAccount<'info>,
+}
+
 #[derive(Accounts)]
 pub struct EnableRefundsIfDeadlinePassed<'info> {
     #[account(mut)]
@@ -283,6 +927,13 @@ pub struct ClaimRefund<'info> {
     )]
     /// CHECK: Public SOL vault holding contributions
     pub public_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"creator_bond", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Creator bond PDA (pays the slashed bond share)
+    pub creator_bond: UncheckedAccount<'info>,
     #[account(
         mut,
         seeds = [b"position", presale.key().as_ref(), user.key().as_ref()],
@@ -303,12 +954,21 @@ pub struct FinalizePresale<'info> {
     pub platform: Account<'info, PlatformConfig>,
     #[account(mut)]
     pub presale: Account<'info, Presale>,
-    pub admin: Signer<'info>,
+    /// Anyone after public_end_ts; admin-only before that
+    pub caller: Signer<'info>,
 }
 
 impl<'info> FinalizePresale<'info> {
-    pub fn validate(&self) -> Result<()> {
-        assert_admin(&self.platform, &self.admin.key())?;
+    /// Finalizing is permissionless once public_end_ts has passed. Finalizing early is an
+    /// admin-only escape hatch for presales with time checks disabled.
+    pub fn validate(&self, now: i64) -> Result<()> {
+        if now < self.presale.public_end_ts {
+            require!(
+                !self.presale.time_checks_enabled(),
+                crate::errors::PresaleError::PresaleNotEnded
+            );
+            assert_admin(&self.platform, &self.caller.key())?;
+        }
         Ok(())
     }
 }
@@ -341,38 +1001,184 @@ pub struct MigrateAndCreateLp<'info> {
     )]
     /// CHECK: Public SOL vault
     pub public_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vip_sol_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: VIP SOL vault PDA (may not exist if the presale has no VIP pool)
+    pub vip_sol_vault: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    /// CHECK: Platform fee vault PDA
+    pub fee_vault: UncheckedAccount<'info>,
     #[account(
         mut,
         seeds = [b"ecosystem_vault", presale.key().as_ref()],
         bump
     )]
     pub ecosystem_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    /// CHECK: LP token account (temporary, for LP creation)
-    pub lp_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    /// CHECK: LP SOL account (temporary, for LP creation)
-    pub lp_sol_account: UncheckedAccount<'info>,
+    #[account(address = presale.mint)]
+    pub mint: Box<Account<'info, Mint>>,
+    /// Wrapped SOL, the pool's quote side
+    #[account(address = spl_token::native_mint::ID)]
+    pub quote_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"lp_authority", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: System-owned PDA that creates the pool and receives the LP tokens
+    pub lp_authority: UncheckedAccount<'info>,
+    /// lp_authority's deposit account for the LP tokens side
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = lp_authority,
+    )]
+    pub lp_base_account: Box<Account<'info, TokenAccount>>,
+    /// lp_authority's wrapped SOL deposit account
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = quote_mint,
+        associated_token::authority = lp_authority,
+    )]
+    pub lp_quote_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: AMM program (must be the platform's program for params.amm)
+    pub amm_program: UncheckedAccount<'info>,
     #[account(mut)]
     /// CHECK: Treasury account
     pub treasury: UncheckedAccount<'info>,
+    /// Revenue split config; required when presale.has_influencer_config
+    #[account(
+        seeds = [b"influencer", presale.key().as_ref()],
+        bump = influencer_config.bump
+    )]
+    pub influencer_config: Option<Account<'info, InfluencerConfig>>,
+    #[account(mut)]
+    /// CHECK: Creator payout account (must match influencer_config.creator)
+    pub creator: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"vip_pool", presale.key().as_ref()],
+        bump = vip_pool.bump
+    )]
+    pub vip_pool: Option<Account<'info, VipPool>>,
+    #[account(
+        mut,
+        seeds = [b"vip_rewards_vault", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: VIP rewards vault PDA (required with vip_pool, i.e. when VIP SOL was raised)
+    pub vip_rewards_vault: Option<UncheckedAccount<'info>>,
     #[account(mut)]
     pub admin: Signer<'info>,
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 impl<'info> MigrateAndCreateLp<'info> {
-    pub fn validate(&self) -> Result<()> {
+    pub fn validate(&self, amm: AmmKind) -> Result<()> {
         assert_admin(&self.platform, &self.admin.key())?;
+        let amm_program = match amm {
+            AmmKind::PumpSwap => self.platform.pumpswap_program,
+            AmmKind::RaydiumCpmm => self.platform.raydium_cpmm_program,
+        };
+        require!(
+            self.amm_program.key() == amm_program,
+            crate::errors::PresaleError::Unauthorized
+        );
         require!(
             self.treasury.key() == self.platform.treasury,
             crate::errors::PresaleError::Unauthorized
         );
+        // The split can't be skipped by leaving its accounts out
+        require!(
+            self.influencer_config.is_some() == self.presale.has_influencer_config,
+            crate::errors::PresaleError::InvalidPresaleConfig
+        );
+        if let Some(influencer_config) = &self.influencer_config {
+            require!(
+                self.creator.as_ref().map(|creator| creator.key())
+                    == Some(influencer_config.creator),
+                crate::errors::PresaleError::Unauthorized
+            );
+        }
+        if self.presale.vip_raised_lamports > 0 {
+            require!(
+                self.vip_pool.is_some() && self.vip_rewards_vault.is_some(),
+                crate::errors::PresaleError::InvalidPresaleConfig
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UnlockLp<'info> {
+    #[account(mut)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        seeds = [b"lp_authority", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: LP authority PDA (owner of lp_vault)
+    pub lp_authority: UncheckedAccount<'info>,
+    #[account(mut, address = presale.lp_vault)]
+    pub lp_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+    #[account(address = presale.lp_mint)]
+    pub lp_mint: InterfaceAccount<'info, InterfaceMint>,
+    #[account(mut, token::mint = lp_mint, token::token_program = token_program)]
+    pub recipient_token_account: InterfaceAccount<'info, InterfaceTokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> UnlockLp<'info> {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.recipient_token_account.owner == self.presale.lp_lock.recipient,
+            crate::errors::PresaleError::Unauthorized
+        );
         Ok(())
     }
 }
 
+#[derive(Accounts)]
+pub struct BurnLp<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, PlatformConfig>,
+    #[account(mut, has_one = platform)]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        seeds = [b"lp_authority", presale.key().as_ref()],
+        bump
+    )]
+    /// CHECK: LP authority PDA (owner of lp_vault)
+    pub lp_authority: UncheckedAccount<'info>,
+    #[account(mut, address = presale.lp_vault)]
+    pub lp_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+    #[account(mut, address = presale.lp_mint)]
+    pub lp_mint: InterfaceAccount<'info, InterfaceMint>,
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> BurnLp<'info> {
+    pub fn validate(&self) -> Result<()> {
+        assert_admin(&self.platform, &self.admin.key())
+    }
+}
+
 #[derive(Accounts)]
 pub struct ClaimTokens<'info> {
     #[account(mut)]
@@ -402,3 +1208,13 @@ pub struct ClaimTokens<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[derive(Accounts)]
+pub struct GetVestedAmount<'info> {
+    pub presale: Account<'info, Presale>,
+    #[account(
+        seeds = [b"position", presale.key().as_ref(), user_position.user.as_ref()],
+        bump = user_position.bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+}
+
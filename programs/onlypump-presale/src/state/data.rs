@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use crate::errors::PresaleError;
+use crate::events::{CreatorBondSlashed, PhaseChanged};
+use crate::utils::{apply_bps, isqrt, BPS_DENOMINATOR};
 
 // ========== Account Data Structures ==========
 
@@ -8,11 +11,23 @@ pub struct PlatformConfig {
     pub operator: Pubkey,
     pub treasury: Pubkey,
     pub fee_bps: u16,
+    /// Owner proposed via `propose_owner`, must call `accept_owner` (default = none)
+    pub pending_owner: Pubkey,
+    /// Backend key that signs contribution vouchers (default = vouchers disabled)
+    pub voucher_signer: Pubkey,
+    /// Bonding curve program used by launch_on_pumpfun (pump.fun, or a mock in tests)
+    pub pumpfun_program: Pubkey,
+    /// AMM programs migrate_and_create_lp may create the pool on
+    pub pumpswap_program: Pubkey,
+    pub raydium_cpmm_program: Pubkey,
     pub bump: u8,
 }
 
 impl PlatformConfig {
-    pub const LEN: usize = 32 + 32 + 32 + 2 + 1; // owner + operator + treasury + fee_bps + bump
+    pub const LEN: usize = 32 + 32 + 32 + 2 + 32 + 32 + 32 + 32 + 32 + 1; // owner + operator + treasury + fee_bps + pending_owner + voucher_signer + pumpfun_program + pumpswap_program + raydium_cpmm_program + bump
+
+    /// Upper bound for fee_bps (10%)
+    pub const MAX_FEE_BPS: u16 = 1_000;
 }
 
 #[account]
@@ -22,21 +37,55 @@ pub struct Presale {
     pub mint: Pubkey,
     pub public_start_ts: i64,
     pub public_end_ts: i64,
+    /// Whitelist-only window ahead of the public window (both 0 = no whitelist phase)
+    pub whitelist_start_ts: i64,
+    pub whitelist_end_ts: i64,
+    /// Per-tier discount and cap for the whitelist window (index = tier - 1)
+    pub tier_configs: [TierConfig; MAX_WHITELIST_TIERS],
+    /// Per-tier Merkle allowlist roots (index = tier - 1, all zero = not set)
+    pub whitelist_roots: [[u8; 32]; MAX_WHITELIST_TIERS],
     /// Token Generation Event timestamp (when creator is expected to launch)
     pub tge_ts: i64,
-    pub public_token_cap: u64,              // 400M tokens
-    pub lp_token_allocation: u64,           // 300M tokens
-    pub ecosystem_allocation: u64,          // 100M tokens
+    /// Unlock schedule for claimed tokens, measured from tge_ts
+    pub vesting: VestingConfig,
+    /// How a position's token entitlement is computed at claim time
+    pub claim_mode: ClaimMode,
+    /// Tokens deposited into token_vault through fund_presale_tokens
+    pub funded_tokens: u64,
+    /// Set by seal_funding: no more deposits or launch withdrawals, claims allowed
+    pub funding_sealed: bool,
+    /// Final split of funded_tokens recorded by seal_funding.
+    /// funded_public_tokens backs claims (ProRata entitlement base); LP and ecosystem
+    /// amounts are what migrate_and_create_lp moves out.
+    pub funded_public_tokens: u64,
+    pub funded_lp_tokens: u64,
+    pub funded_ecosystem_tokens: u64,
+    /// Opt-out of public_start_ts/public_end_ts enforcement and of the 24h launch grace
+    /// (test deployments only)
+    pub time_checks_disabled: bool,
+    pub public_token_cap: u64,              // e.g. 400M tokens
+    pub lp_token_allocation: u64,           // e.g. 300M tokens
+    pub ecosystem_allocation: u64,          // e.g. 100M tokens (0 = no ecosystem bucket)
+    /// Decimals the allocations above are expressed in; checked against the mint in initialize_vaults
+    pub token_decimals: u8,
     pub public_price_lamports_per_token: u64,
     /// Public presale hard cap (in lamports)
     pub hard_cap_lamports: u64,
+    /// Minimum raise (in lamports); below it finalize_presale goes straight to Refundable
+    pub soft_cap_lamports: u64,
+    /// Smallest single contribution accepted (0 = no minimum)
+    pub min_contribution_lamports: u64,
+    /// Cumulative cap per wallet across all contributions (0 = no cap)
+    pub max_contribution_per_wallet_lamports: u64,
     pub public_raised_lamports: u64,
-    /// Total VIP raise reflected on-chain (optional, can be updated by admin)
+    /// Sum of tokens_allocated across all public and VIP positions (bounded by public_token_cap)
+    pub total_tokens_allocated: u64,
+    /// Total raised by contribute_vip (held in vip_sol_vault)
     pub vip_raised_lamports: u64,
     pub is_finalized: bool,
     pub is_migrated: bool,
-    /// Current presale phase (0 = Pending, 1 = PublicActive, 2 = Voting, 3 = Launchable, 4 = Refundable, 5 = Launched)
-    pub phase: u8,
+    /// Current presale phase, only changed through `Presale::transition_to`
+    pub phase: PresalePhase,
     /// Voting weights for launch vs refund decisions
     pub vote_yes_weight: u64,
     pub vote_no_weight: u64,
@@ -50,36 +99,101 @@ pub struct Presale {
     pub outcome: u8,
     pub ecosystem_vault: Pubkey,
     pub lp_authority: Pubkey,
+    pub lp_lock: LpLockConfig,
+    /// Set at migration: LP mint and lp_authority's LP token account (the lock vault)
+    pub lp_mint: Pubkey,
+    pub lp_vault: Pubkey,
+    /// LP tokens still held in lp_vault (0 once unlocked or burned)
+    pub lp_locked_amount: u64,
+    pub lp_unlock_ts: i64,
+    pub escrow: EscrowConfig,
+    /// Share of the raise (in bps) paid out to the creator so far; refunds cover the rest
+    pub escrow_released_bps: u16,
+    /// Set by the first escrow tranche: funding must be sealed by then or refunds open up
+    pub escrow_deadline_ts: i64,
+    /// SOL the creator posted into creator_bond at create_presale (0 once returned)
+    pub creator_bond_lamports: u64,
+    /// Set when the creator missed a deadline: refunds then include a pro-rata bond share
+    pub creator_bond_slashed: bool,
+    /// Quorum / approval thresholds applied by resolve_vote
+    pub vote_config: VoteConfig,
+    /// Incremented by every start_vote; VoteReceipts are per round
+    pub vote_round: u32,
+    /// Unweighted contribution lamports that voted in the current round (for the quorum)
+    pub vote_turnout_lamports: u64,
+    /// Set by configure_influencer: migration must then apply the revenue split
+    pub has_influencer_config: bool,
     pub bump: u8,
 }
 
 impl Presale {
     // 3 * Pubkey (platform, authority, mint)
     // 3 * i64 (public_start_ts, public_end_ts, tge_ts)
+    // 1 * VestingConfig (vesting)
+    // 1 * u8 (claim_mode, borsh enum tag)
+    // 1 * u64 (funded_tokens)
+    // 1 * bool (funding_sealed)
+    // 3 * u64 (funded_public_tokens, funded_lp_tokens, funded_ecosystem_tokens)
+    // 2 * i64 (whitelist_start_ts, whitelist_end_ts)
+    // MAX_WHITELIST_TIERS * TierConfig (tier_configs)
+    // MAX_WHITELIST_TIERS * [u8; 32] (whitelist_roots)
+    // 1 * bool (time_checks_disabled)
     // 4 * u64 (caps, price, hard cap, public_raised)
+    // 1 * u64 (soft_cap_lamports)
+    // 2 * u64 (min_contribution_lamports, max_contribution_per_wallet_lamports)
+    // 1 * u8 (token_decimals)
+    // 1 * u64 (total_tokens_allocated)
     // 1 * u64 (vip_raised_lamports)
     // 2 * bool (is_finalized, is_migrated)
-    // 1 * u8 (phase)
+    // 1 * u8 (phase, borsh enum tag)
     // 2 * u64 (vote_yes_weight, vote_no_weight)
     // 2 * i64 (voting_ends_ts, launch_deadline_ts)
     // 1 * bool (refund_enabled)
     // 1 * u8 (outcome)
     // 2 * Pubkey (ecosystem_vault, lp_authority)
+    // 1 * LpLockConfig (lp_lock)
+    // 2 * Pubkey (lp_mint, lp_vault)
+    // 1 * u64 (lp_locked_amount)
+    // 1 * i64 (lp_unlock_ts)
+    // 1 * EscrowConfig (escrow)
+    // 1 * u16 (escrow_released_bps)
+    // 1 * i64 (escrow_deadline_ts)
+    // 1 * u64 (creator_bond_lamports)
+    // 1 * bool (creator_bond_slashed)
+    // 1 * VoteConfig (vote_config)
+    // 1 * u32 (vote_round)
+    // 1 * u64 (vote_turnout_lamports)
+    // 1 * bool (has_influencer_config)
     // 1 * u8 (bump)
-    // Total bytes calculated explicitly:
-    // 3*32 + 3*8 + 5*8 + 2*1 + 1 + 2*8 + 2*8 + 1 + 1 + 2*32 + 1 = 323 bytes
     pub const LEN: usize = 32  // platform
         + 32                   // authority
         + 32                   // mint
         + 8                    // public_start_ts
         + 8                    // public_end_ts
+        + 8                    // whitelist_start_ts
+        + 8                    // whitelist_end_ts
+        + MAX_WHITELIST_TIERS * TierConfig::LEN // tier_configs
+        + MAX_WHITELIST_TIERS * 32 // whitelist_roots
         + 8                    // tge_ts
+        + VestingConfig::LEN   // vesting
+        + 1                    // claim_mode
+        + 8                    // funded_tokens
+        + 1                    // funding_sealed
+        + 8                    // funded_public_tokens
+        + 8                    // funded_lp_tokens
+        + 8                    // funded_ecosystem_tokens
+        + 1                    // time_checks_disabled
         + 8                    // public_token_cap
         + 8                    // lp_token_allocation
         + 8                    // ecosystem_allocation
+        + 1                    // token_decimals
         + 8                    // public_price_lamports_per_token
         + 8                    // hard_cap_lamports
+        + 8                    // soft_cap_lamports
+        + 8                    // min_contribution_lamports
+        + 8                    // max_contribution_per_wallet_lamports
         + 8                    // public_raised_lamports
+        + 8                    // total_tokens_allocated
         + 8                    // vip_raised_lamports
         + 1                    // is_finalized
         + 1                    // is_migrated
@@ -92,7 +206,449 @@ impl Presale {
         + 1                    // outcome
         + 32                   // ecosystem_vault
         + 32                   // lp_authority
+        + LpLockConfig::LEN    // lp_lock
+        + 32                   // lp_mint
+        + 32                   // lp_vault
+        + 8                    // lp_locked_amount
+        + 8                    // lp_unlock_ts
+        + EscrowConfig::LEN    // escrow
+        + 2                    // escrow_released_bps
+        + 8                    // escrow_deadline_ts
+        + 8                    // creator_bond_lamports
+        + 1                    // creator_bond_slashed
+        + VoteConfig::LEN      // vote_config
+        + 4                    // vote_round
+        + 8                    // vote_turnout_lamports
+        + 1                    // has_influencer_config
         + 1;                   // bump
+
+    /// Move the presale to `next`, rejecting transitions the state machine doesn't allow.
+    /// Every phase change goes through here so PhaseChanged is always emitted.
+    pub fn transition_to(&mut self, presale: Pubkey, next: PresalePhase) -> Result<()> {
+        require!(
+            self.phase.can_transition_to(next),
+            PresaleError::InvalidPhaseTransition
+        );
+
+        let from = self.phase;
+        self.phase = next;
+
+        emit!(PhaseChanged {
+            presale,
+            from,
+            to: next,
+        });
+
+        Ok(())
+    }
+
+    /// Creator missed a deadline: the bond becomes claimable by contributors via refunds
+    pub fn slash_creator_bond(&mut self, presale: Pubkey) {
+        if self.creator_bond_lamports == 0 || self.creator_bond_slashed {
+            return;
+        }
+        self.creator_bond_slashed = true;
+
+        emit!(CreatorBondSlashed {
+            presale,
+            amount_lamports: self.creator_bond_lamports,
+        });
+    }
+
+    /// Count `weight` for the launch (yes) or refund (no) side of the current vote and
+    /// `stake_lamports` (the unweighted contribution behind it) towards turnout
+    pub fn add_vote(&mut self, support_launch: bool, weight: u64, stake_lamports: u64) -> Result<()> {
+        let tally = if support_launch {
+            &mut self.vote_yes_weight
+        } else {
+            &mut self.vote_no_weight
+        };
+        *tally = tally.checked_add(weight).ok_or(PresaleError::MathOverflow)?;
+        self.vote_turnout_lamports = self
+            .vote_turnout_lamports
+            .checked_add(stake_lamports)
+            .ok_or(PresaleError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Take back a vote previously counted by add_vote (vote changes)
+    pub fn remove_vote(
+        &mut self,
+        support_launch: bool,
+        weight: u64,
+        stake_lamports: u64,
+    ) -> Result<()> {
+        let tally = if support_launch {
+            &mut self.vote_yes_weight
+        } else {
+            &mut self.vote_no_weight
+        };
+        *tally = tally.checked_sub(weight).ok_or(PresaleError::MathOverflow)?;
+        self.vote_turnout_lamports = self
+            .vote_turnout_lamports
+            .checked_sub(stake_lamports)
+            .ok_or(PresaleError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Launch tranche paid but the escrowed remainder not yet released (or refunded):
+    /// the SOL left in the vaults belongs to release_escrow / escrow refunds
+    pub fn escrow_pending(&self) -> bool {
+        self.escrow_released_bps > 0 && (self.escrow_released_bps as u64) < BPS_DENOMINATOR
+    }
+
+    /// Public plus VIP raise; this is what the soft cap is measured against
+    pub fn total_raised_lamports(&self) -> Result<u64> {
+        self.public_raised_lamports
+            .checked_add(self.vip_raised_lamports)
+            .ok_or(PresaleError::MathOverflow.into())
+    }
+
+    /// Lamports refunded for `contribution`: whatever part of the raise was not yet paid out
+    /// to the creator (the escrow remainder), i.e. all of it if nothing was released
+    pub fn refund_amount(&self, contribution: u64) -> Result<u64> {
+        let unreleased_bps = (BPS_DENOMINATOR as u16)
+            .checked_sub(self.escrow_released_bps)
+            .ok_or(PresaleError::MathOverflow)?;
+        apply_bps(contribution, unreleased_bps)
+    }
+
+    /// Slashed creator bond owed to a refunding contributor:
+    /// creator_bond_lamports * contribution / total raised (0 unless the bond was slashed)
+    pub fn bond_share(&self, contribution: u64) -> Result<u64> {
+        let total_raised = self.total_raised_lamports()?;
+        if !self.creator_bond_slashed || total_raised == 0 {
+            return Ok(0);
+        }
+        let share = (self.creator_bond_lamports as u128)
+            .checked_mul(contribution as u128)
+            .ok_or(PresaleError::MathOverflow)?
+            / total_raised as u128;
+        u64::try_from(share).map_err(|_| PresaleError::MathOverflow.into())
+    }
+
+    /// Total tokens the presale must be funded with (public + LP + ecosystem)
+    pub fn total_token_allocation(&self) -> Result<u64> {
+        self.public_token_cap
+            .checked_add(self.lp_token_allocation)
+            .and_then(|total| total.checked_add(self.ecosystem_allocation))
+            .ok_or(PresaleError::MathOverflow.into())
+    }
+
+    /// Base units of the token bought by `lamports` at `price_lamports_per_token`
+    /// (price is per whole token, i.e. 10^token_decimals base units)
+    pub fn tokens_for_lamports(&self, lamports: u64, price_lamports_per_token: u64) -> Result<u64> {
+        let tokens = (lamports as u128)
+            .checked_mul(10u128.pow(self.token_decimals as u32))
+            .ok_or(PresaleError::MathOverflow)?
+            .checked_div(price_lamports_per_token as u128)
+            .ok_or(PresaleError::MathOverflow)?;
+        u64::try_from(tokens).map_err(|_| PresaleError::MathOverflow.into())
+    }
+
+    /// Lamports needed to buy `tokens` base units at `price_lamports_per_token`, rounded down
+    /// so that `tokens_for_lamports` of the result never exceeds `tokens`
+    pub fn lamports_for_tokens(&self, tokens: u64, price_lamports_per_token: u64) -> Result<u64> {
+        let lamports = (tokens as u128)
+            .checked_mul(price_lamports_per_token as u128)
+            .ok_or(PresaleError::MathOverflow)?
+            / 10u128.pow(self.token_decimals as u32);
+        // Saturate: more room than u64 lamports is effectively unlimited
+        Ok(u64::try_from(lamports).unwrap_or(u64::MAX))
+    }
+
+    /// Whether public_start_ts/public_end_ts are enforced for this presale
+    pub fn time_checks_enabled(&self) -> bool {
+        !self.time_checks_disabled
+    }
+
+    /// Tokens a position is entitled to: its fixed-price allocation, or in ProRata mode
+    /// funded_public_tokens * contribution_lamports / total raised
+    pub fn token_entitlement(&self, contribution_lamports: u64, tokens_allocated: u64) -> Result<u64> {
+        match self.claim_mode {
+            ClaimMode::FixedPrice => Ok(tokens_allocated),
+            ClaimMode::ProRata => {
+                let total_raised = self.total_raised_lamports()?;
+                if total_raised == 0 {
+                    return Ok(0);
+                }
+                let tokens = (self.funded_public_tokens as u128)
+                    .checked_mul(contribution_lamports as u128)
+                    .ok_or(PresaleError::MathOverflow)?
+                    / total_raised as u128;
+                u64::try_from(tokens).map_err(|_| PresaleError::MathOverflow.into())
+            }
+        }
+    }
+
+    /// Part of `total_tokens` a position may have claimed by `now` under the vesting schedule
+    pub fn vested_amount(&self, total_tokens: u64, now: i64) -> Result<u64> {
+        self.vesting.vested_amount(total_tokens, self.tge_ts, now)
+    }
+
+    /// Which contribution window is open at `now`.
+    /// With time checks disabled the public window is always open.
+    pub fn contribution_window(&self, now: i64) -> ContributionWindow {
+        if !self.time_checks_enabled()
+            || (now >= self.public_start_ts && now < self.public_end_ts)
+        {
+            ContributionWindow::Public
+        } else if self.whitelist_end_ts > self.whitelist_start_ts
+            && now >= self.whitelist_start_ts
+            && now < self.whitelist_end_ts
+        {
+            ContributionWindow::Whitelist
+        } else {
+            ContributionWindow::Closed
+        }
+    }
+
+    /// Config for whitelist `tier` (1-based); None for tier 0 or unknown tiers
+    pub fn tier_config(&self, tier: u8) -> Option<&TierConfig> {
+        (tier as usize)
+            .checked_sub(1)
+            .and_then(|index| self.tier_configs.get(index))
+    }
+
+    /// Merkle allowlist root for whitelist `tier` (1-based); None for tier 0 or unknown tiers
+    pub fn whitelist_root(&self, tier: u8) -> Option<&[u8; 32]> {
+        (tier as usize)
+            .checked_sub(1)
+            .and_then(|index| self.whitelist_roots.get(index))
+    }
+}
+
+/// Which contributors may contribute right now
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ContributionWindow {
+    /// Only holders of a whitelist grant (tier >= 1), at their tier's price and cap
+    Whitelist,
+    /// Everyone, at the public price
+    Public,
+    Closed,
+}
+
+/// Number of whitelist tiers (tiers are 1..=MAX_WHITELIST_TIERS, 0 = not whitelisted)
+pub const MAX_WHITELIST_TIERS: usize = 3;
+
+/// Whitelist-window terms for one tier
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct TierConfig {
+    /// Discount off public_price_lamports_per_token (1000 = 10% cheaper)
+    pub discount_bps: u16,
+    /// Cumulative cap per wallet in this tier (0 = no tier cap)
+    pub max_contribution_lamports: u64,
+}
+
+impl TierConfig {
+    pub const LEN: usize = 2 + 8;
+}
+
+/// How claims turn a contribution into tokens
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ClaimMode {
+    /// tokens_allocated, computed at contribution time from the price
+    #[default]
+    FixedPrice,
+    /// Share of the public tokens actually funded, proportional to lamports contributed
+    ProRata,
+}
+
+/// Tranche release of the raised SOL to the creator.
+/// withdraw_for_launch pays out launch_tranche_bps of the raise; release_escrow pays the rest
+/// once the token vault is funded and sealed. If that does not happen within
+/// release_delay_seconds of the first tranche, enable_escrow_refunds lets contributors
+/// reclaim their share of what is still escrowed.
+/// launch_tranche_bps == 0 disables escrow (withdraw_for_launch pays everything at once).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EscrowConfig {
+    pub launch_tranche_bps: u16,
+    pub release_delay_seconds: i64,
+}
+
+impl EscrowConfig {
+    pub const LEN: usize = 2 + 8;
+
+    pub fn is_enabled(&self) -> bool {
+        self.launch_tranche_bps > 0
+    }
+
+    pub fn is_valid(&self) -> bool {
+        (self.launch_tranche_bps as u64) < BPS_DENOMINATOR
+            && self.release_delay_seconds >= 0
+            && (self.is_enabled() || self.release_delay_seconds == 0)
+    }
+}
+
+/// Vote weighting and the thresholds resolve_vote applies to the tallies:
+/// - weight: public lamports + VIP lamports * vip_weight_bps / 10000, square-rooted when
+///   quadratic is set to dampen whales
+/// - quorum: the contribution lamports that voted (unweighted) must reach quorum_bps of the
+///   total raise (public + VIP), otherwise the presale takes default_outcome (LAUNCH or REFUND)
+/// - approval: launch needs yes > no and yes >= approval_bps of the votes cast
+///   (anything up to 5000 is a simple majority, 10000 is unanimity)
+///
+/// quorum_bps == 0 and approval_bps == 0 keep the plain yes > no rule.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoteConfig {
+    pub quorum_bps: u16,
+    pub approval_bps: u16,
+    pub default_outcome: u8,
+    /// VIP contribution multiplier (10000 = same weight as public, 0 = VIP cannot vote)
+    pub vip_weight_bps: u16,
+    pub quadratic: bool,
+}
+
+impl VoteConfig {
+    pub const LEN: usize = 2 + 2 + 1 + 2 + 1;
+
+    pub fn is_valid(&self) -> bool {
+        use crate::instructions::vote::outcome;
+        (self.quorum_bps as u64) <= BPS_DENOMINATOR
+            && (self.approval_bps as u64) <= BPS_DENOMINATOR
+            && (self.default_outcome == outcome::LAUNCH || self.default_outcome == outcome::REFUND)
+    }
+
+    /// VIP lamports that take part in the vote (none when vip_weight_bps == 0, so they
+    /// don't count toward turnout either)
+    pub fn voting_vip_lamports(&self, vip_lamports: u64) -> u64 {
+        if self.vip_weight_bps > 0 {
+            vip_lamports
+        } else {
+            0
+        }
+    }
+
+    /// Vote weight of a voter with `public_lamports` and `vip_lamports` contributed
+    pub fn vote_weight(&self, public_lamports: u64, vip_lamports: u64) -> Result<u64> {
+        let stake = public_lamports as u128
+            + vip_lamports as u128 * self.vip_weight_bps as u128 / BPS_DENOMINATOR as u128;
+        let weight = if self.quadratic { isqrt(stake) } else { stake };
+        u64::try_from(weight).map_err(|_| PresaleError::MathOverflow.into())
+    }
+
+    /// Whether `turnout_lamports` reaches quorum_bps of `total_raised`
+    pub fn quorum_reached(&self, turnout_lamports: u64, total_raised: u64) -> bool {
+        turnout_lamports as u128 * BPS_DENOMINATOR as u128
+            >= total_raised as u128 * self.quorum_bps as u128
+    }
+
+    /// Whether the votes cast approve the launch
+    pub fn approved(&self, yes: u64, no: u64) -> bool {
+        let turnout = yes as u128 + no as u128;
+        yes > no
+            && yes as u128 * BPS_DENOMINATOR as u128 >= turnout * self.approval_bps as u128
+    }
+}
+
+/// Lock on the LP tokens minted to lp_authority at migration
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LpLockConfig {
+    /// Seconds after migration before unlock_lp can release the LP tokens (0 = no lock)
+    pub lock_seconds: i64,
+    /// Receives the LP tokens on unlock (Pubkey::default() = presale authority)
+    pub recipient: Pubkey,
+}
+
+impl LpLockConfig {
+    pub const LEN: usize = 8 + 32;
+}
+
+/// Token unlock schedule relative to tge_ts:
+/// tge_unlock_bps unlocks at tge_ts, the rest vests linearly over duration_seconds once
+/// cliff_seconds have passed, in step_seconds increments (0 = continuous).
+/// An all-zero config means no vesting: everything is claimable as soon as the presale migrates.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VestingConfig {
+    pub tge_unlock_bps: u16,
+    pub cliff_seconds: i64,
+    pub duration_seconds: i64,
+    pub step_seconds: i64,
+}
+
+impl VestingConfig {
+    pub const LEN: usize = 2 + 8 + 8 + 8;
+
+    pub fn is_enabled(&self) -> bool {
+        *self != VestingConfig::default()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.tge_unlock_bps as u64 <= crate::utils::BPS_DENOMINATOR
+            && self.cliff_seconds >= 0
+            && self.duration_seconds >= 0
+            && self.step_seconds >= 0
+            && self.step_seconds <= self.duration_seconds
+    }
+
+    /// Part of `total` unlocked at `now` for a schedule starting at `tge_ts`
+    pub fn vested_amount(&self, total: u64, tge_ts: i64, now: i64) -> Result<u64> {
+        if !self.is_enabled() {
+            return Ok(total);
+        }
+        if now < tge_ts {
+            return Ok(0);
+        }
+
+        let tge_amount = apply_bps(total, self.tge_unlock_bps)?;
+        let linear_start = tge_ts.saturating_add(self.cliff_seconds);
+        if now < linear_start {
+            return Ok(tge_amount);
+        }
+
+        let mut elapsed = now - linear_start;
+        if self.step_seconds > 0 {
+            elapsed -= elapsed % self.step_seconds;
+        }
+        if elapsed >= self.duration_seconds {
+            return Ok(total);
+        }
+
+        // tge_amount + (total - tge_amount) * elapsed / duration_seconds
+        let linear = ((total - tge_amount) as u128)
+            .checked_mul(elapsed as u128)
+            .ok_or(PresaleError::MathOverflow)?
+            / self.duration_seconds as u128;
+        let linear = u64::try_from(linear).map_err(|_| PresaleError::MathOverflow)?;
+        tge_amount
+            .checked_add(linear)
+            .ok_or(PresaleError::MathOverflow.into())
+    }
+}
+
+/// Presale lifecycle: Pending -> PublicActive -> Voting -> Launchable -> Launched,
+/// with Refundable reachable from PublicActive (soft cap missed), Voting (refund wins)
+/// and Launchable (deadline missed)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PresalePhase {
+    Pending,
+    PublicActive,
+    Voting,
+    Launchable,
+    Refundable,
+    Launched,
+}
+
+impl PresalePhase {
+    pub fn can_transition_to(self, next: PresalePhase) -> bool {
+        use PresalePhase::*;
+        matches!(
+            (self, next),
+            (Pending, PublicActive)
+                | (PublicActive, Voting)
+                // Soft cap missed at finalize
+                | (PublicActive, Refundable)
+                // start_vote may restart a vote before its voting_ends_ts
+                | (Voting, Voting)
+                | (Voting, Launchable)
+                | (Voting, Refundable)
+                | (Launchable, Launched)
+                // Creator missed the launch deadline
+                | (Launchable, Refundable)
+                // Escrow: the creator took the launch tranche but never sealed funding
+                | (Launched, Refundable)
+        )
+    }
 }
 
 #[account]
@@ -103,13 +659,36 @@ pub struct UserPosition {
     pub tokens_allocated: u64,
     pub tokens_claimed: u64,
     pub refunded: bool,
-    /// Whether this position has already voted in the current vote
-    pub has_voted: bool,
+    /// Highest contribution voucher nonce redeemed by this wallet (vouchers must strictly increase)
+    pub voucher_nonce: u64,
+    /// Wallet voting for this position (Pubkey::default() = no delegation)
+    pub delegate: Pubkey,
     pub bump: u8,
 }
 
 impl UserPosition {
-    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 1 + 1;
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 8 + 32 + 1;
+}
+
+/// One voter's ballot in one vote round
+/// PDA seeds: ["vote", presale_pubkey, vote_round (u32 LE), voter_pubkey]
+#[account]
+pub struct VoteReceipt {
+    pub presale: Pubkey,
+    pub voter: Pubkey,
+    /// The voter, or the delegate who cast this ballot for them
+    pub cast_by: Pubkey,
+    pub round: u32,
+    pub support_launch: bool,
+    /// Weight counted in the tally (see VoteConfig::vote_weight)
+    pub weight: u64,
+    /// Unweighted contribution lamports counted towards turnout
+    pub stake_lamports: u64,
+    pub bump: u8,
+}
+
+impl VoteReceipt {
+    pub const LEN: usize = 32 + 32 + 32 + 4 + 1 + 8 + 8 + 1;
 }
 
 #[account]
@@ -125,8 +704,12 @@ impl WhitelistEntry {
     pub const LEN: usize = 32 + 32 + 1 + 8 + 1;
 }
 
-// ========== VIP Structures (Placeholders for Future) ==========
+// ========== VIP Structures ==========
 
+/// How the SOL paid out at migration is split between creator, platform and VIP holders.
+/// creator_share_bps of the payout goes to the creator, the rest to the platform treasury;
+/// each side then hands its vip_share_* of that to VIP holders.
+/// PDA seeds: ["influencer", presale_pubkey]
 #[account]
 pub struct InfluencerConfig {
     pub creator: Pubkey,
@@ -141,27 +724,190 @@ impl InfluencerConfig {
     pub const LEN: usize = 32 + 32 + 2 + 2 + 2 + 1;
 }
 
+/// VIP round of a presale: own SOL vault (["vip_sol_vault", presale]), price and cap.
+/// VIP tokens come out of the same public_token_cap as public contributions.
+/// PDA seeds: ["vip_pool", presale_pubkey]
 #[account]
 pub struct VipPool {
     pub presale: Pubkey,
+    pub price_lamports_per_token: u64,
+    /// VIP hard cap (in lamports), separate from the public hard cap
+    pub hard_cap_lamports: u64,
     pub total_contributions: u64,
+    /// Sum of tokens_allocated across all VIP positions
+    pub total_tokens_allocated: u64,
+    /// Revenue share paid into vip_rewards_vault, split pro-rata by contribution
+    pub total_rewards_lamports: u64,
     pub bump: u8,
 }
 
 impl VipPool {
-    pub const LEN: usize = 32 + 8 + 1;
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Share of total_rewards_lamports earned by a VIP contribution of `contribution_lamports`
+    pub fn rewards_for(&self, contribution_lamports: u64) -> Result<u64> {
+        if self.total_contributions == 0 {
+            return Ok(0);
+        }
+        let rewards = (self.total_rewards_lamports as u128)
+            .checked_mul(contribution_lamports as u128)
+            .ok_or(PresaleError::MathOverflow)?
+            / self.total_contributions as u128;
+        u64::try_from(rewards).map_err(|_| PresaleError::MathOverflow.into())
+    }
 }
 
+/// PDA seeds: ["vip_position", vip_pool_pubkey, user_pubkey]
 #[account]
 pub struct VipPosition {
     pub vip_pool: Pubkey,
     pub user: Pubkey,
     pub contribution_lamports: u64,
+    pub tokens_allocated: u64,
+    pub tokens_claimed: u64,
+    /// Pro-rata share of VipPool.total_rewards_lamports, refreshed by claim_vip_rewards
     pub rewards_earned: u64,
+    pub rewards_claimed: u64,
+    pub refunded: bool,
+    /// Highest contribution voucher nonce redeemed in the VIP round
+    pub voucher_nonce: u64,
     pub bump: u8,
 }
 
 impl VipPosition {
-    pub const LEN: usize = 32 + 32 + 8 + 8 + 1;
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1;
+}
+
+// ========== Instruction Parameters ==========
+
+/// Fields to change in `update_platform_config`; `None` leaves the value untouched
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdatePlatformConfigParams {
+    pub operator: Option<Pubkey>,
+    pub treasury: Option<Pubkey>,
+    pub fee_bps: Option<u16>,
+    /// Pubkey::default() disables vouchers
+    pub voucher_signer: Option<Pubkey>,
+    pub pumpfun_program: Option<Pubkey>,
+    pub pumpswap_program: Option<Pubkey>,
+    pub raydium_cpmm_program: Option<Pubkey>,
+}
+
+/// AMM that migrate_and_create_lp seeds the pool on
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AmmKind {
+    PumpSwap,
+    RaydiumCpmm,
+}
+
+/// Pool parameters for `migrate_and_create_lp`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MigrateAndCreateLpParams {
+    pub amm: AmmKind,
+    /// SOL (wrapped) paired with funded_lp_tokens in the pool
+    pub lp_sol_amount: u64,
+    /// SOL lent to lp_authority for the pool accounts' rent and AMM creation fees;
+    /// whatever is not spent returns to the vault
+    pub pool_creation_lamports: u64,
+}
+
+/// Token metadata and buy size for `launch_on_pumpfun`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LaunchOnPumpfunParams {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    /// Tokens (base units) to buy on the bonding curve into token_vault
+    pub token_amount: u64,
+    /// Slippage bound for the buy, at most the SOL available after the platform fee
+    pub max_sol_cost: u64,
+}
+
+/// Token split for a presale, in base units of a mint with `decimals` decimals
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenAllocation {
+    pub public_tokens: u64,
+    pub lp_tokens: u64,
+    /// May be 0 for presales without an ecosystem bucket
+    pub ecosystem_tokens: u64,
+    pub decimals: u8,
+}
+
+impl TokenAllocation {
+    /// Upper bound keeping 10^decimals (and lamports * 10^decimals) in range
+    pub const MAX_DECIMALS: u8 = 12;
+}
+
+/// Arguments for `create_presale`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreatePresaleParams {
+    pub public_start_ts: i64,
+    pub public_end_ts: i64,
+    /// Whitelist-only window, must end by public_start_ts (both 0 = no whitelist phase)
+    pub whitelist_start_ts: i64,
+    pub whitelist_end_ts: i64,
+    /// Tier 1..=MAX_WHITELIST_TIERS terms during the whitelist window
+    pub tiers: [TierConfig; MAX_WHITELIST_TIERS],
+    pub public_price_lamports_per_token: u64,
+    pub hard_cap_lamports: u64,
+    /// 0 = no minimum raise
+    pub soft_cap_lamports: u64,
+    /// 0 = no minimum contribution
+    pub min_contribution_lamports: u64,
+    /// 0 = no per-wallet cap
+    pub max_contribution_per_wallet_lamports: u64,
+    /// Skip public window enforcement in contribute/finalize and the 24h launch grace
+    /// (test deployments only)
+    pub time_checks_disabled: bool,
+    pub allocation: TokenAllocation,
+    /// Unlock schedule from tge_ts (default = no vesting)
+    pub vesting: VestingConfig,
+    pub claim_mode: ClaimMode,
+    pub lp_lock: LpLockConfig,
+    /// Tranche release of the raise (default = no escrow)
+    pub escrow: EscrowConfig,
+    /// SOL the creator (presale authority) posts as a bond; 0 = no bond
+    pub creator_bond_lamports: u64,
+    pub vote_config: VoteConfig,
+}
+
+/// Contribution pool an off-chain grant is issued for; part of the Merkle leaf and the
+/// signed voucher so a grant for one pool can't be redeemed in the other
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AllowlistPool {
+    Public,
+    Vip,
+}
+
+/// Off-chain allowlist membership passed to `contribute_public` / `contribute_vip`
+/// instead of a WhitelistEntry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum AllowlistProof {
+    /// Proof of keccak256(user || pool || tier || max_contribution_lamports) against the tier's root
+    Merkle {
+        tier: u8,
+        max_contribution_lamports: u64,
+        proof: Vec<[u8; 32]>,
+    },
+    /// Voucher signed by `PlatformConfig::voucher_signer`, checked against the Ed25519
+    /// sig-verify instruction right before the contribution
+    Voucher(ContributionVoucher),
+}
+
+/// Whitelist terms issued off-chain by the backend.
+/// The signed message is the Borsh serialization of this struct.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ContributionVoucher {
+    pub presale: Pubkey,
+    pub user: Pubkey,
+    /// Only redeemable in this pool
+    pub pool: AllowlistPool,
+    pub tier: u8,
+    /// 0 = no per-voucher cap
+    pub max_contribution_lamports: u64,
+    /// Unix timestamp after which the voucher is rejected
+    pub expiry_ts: i64,
+    /// Must be greater than the last nonce redeemed by this wallet on this presale
+    pub nonce: u64,
 }
 
@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
 use crate::state::data::PlatformConfig;
 use crate::errors::PresaleError;
+use crate::events::FeeCollected;
+
+/// Basis points denominator (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: u64 = 10_000;
 
 /// Helper function to check if signer is admin (owner or operator)
 pub fn assert_admin(platform: &PlatformConfig, signer: &Pubkey) -> Result<()> {
@@ -11,3 +15,81 @@ pub fn assert_admin(platform: &PlatformConfig, signer: &Pubkey) -> Result<()> {
     Ok(())
 }
 
+/// Helper function to check if signer is the platform owner
+pub fn assert_owner(platform: &PlatformConfig, signer: &Pubkey) -> Result<()> {
+    require!(*signer == platform.owner, PresaleError::Unauthorized);
+    Ok(())
+}
+
+/// Returns `amount * bps / 10_000`, rounded down
+pub fn apply_bps(amount: u64, bps: u16) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(PresaleError::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(value).map_err(|_| PresaleError::MathOverflow.into())
+}
+
+/// Integer square root, rounded down
+pub fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+/// Move lamports between two accounts.
+/// `from` must be owned by this program (PDA vaults created via `init`).
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(PresaleError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(PresaleError::MathOverflow)?;
+    Ok(())
+}
+
+/// Move everything held by the program-owned vault `from` into `to`.
+/// A vault that was never created (or isn't ours) is left alone and 0 is returned.
+pub fn drain_vault(from: &AccountInfo, to: &AccountInfo) -> Result<u64> {
+    if from.owner != &crate::ID {
+        return Ok(0);
+    }
+    let amount = from.lamports();
+    transfer_lamports(from, to, amount)?;
+    Ok(amount)
+}
+
+/// Skim the platform fee on `gross_lamports` leaving `vault` into the platform fee vault.
+/// Returns the fee taken; the caller pays out `gross_lamports - fee`.
+pub fn collect_fee(
+    presale: Pubkey,
+    vault: &AccountInfo,
+    fee_vault: &AccountInfo,
+    gross_lamports: u64,
+    fee_bps: u16,
+) -> Result<u64> {
+    let fee_lamports = apply_bps(gross_lamports, fee_bps)?;
+    if fee_lamports > 0 {
+        transfer_lamports(vault, fee_vault, fee_lamports)?;
+        emit!(FeeCollected {
+            presale,
+            gross_lamports,
+            fee_lamports,
+            fee_bps,
+        });
+    }
+    Ok(fee_lamports)
+}
@@ -6,6 +6,7 @@ import {
   PublicKey,
   SystemProgram,
   LAMPORTS_PER_SOL,
+  Ed25519Program,
 } from "@solana/web3.js";
 import {
   TOKEN_PROGRAM_ID,
@@ -14,9 +15,13 @@ import {
   createMint,
   mintTo,
   getAccount,
+  getAssociatedTokenAddressSync,
   createAssociatedTokenAccountInstruction,
+  NATIVE_MINT,
+  TOKEN_2022_PROGRAM_ID,
 } from "@solana/spl-token";
 import { expect } from "chai";
+import { keccak_256 } from "@noble/hashes/sha3";
 
 describe("onlypump_presale", () => {
   // Configure the client to use the local cluster
@@ -41,6 +46,7 @@ describe("onlypump_presale", () => {
   let publicSolVault: PublicKey;
   let ecosystemVault: PublicKey;
   let ecosystemVaultAuthority: PublicKey;
+  let feeVault: PublicKey;
 
   // Constants
   const TOKEN_DECIMALS = 6;
@@ -49,6 +55,50 @@ describe("onlypump_presale", () => {
   const LP_ALLOCATION = 300_000_000; // 300M tokens
   const VAULT_ALLOCATION = 100_000_000; // 100M tokens
   const TOTAL_PRESALE_TOKENS = PRESALE_ALLOCATION + LP_ALLOCATION + VAULT_ALLOCATION; // 800M
+  const baseUnits = (tokens: number) =>
+    new anchor.BN(tokens).mul(new anchor.BN(10).pow(new anchor.BN(TOKEN_DECIMALS)));
+  // 40/30/10 split of a 1B supply, 6 decimals
+  const defaultAllocation = {
+    publicTokens: baseUnits(PRESALE_ALLOCATION),
+    lpTokens: baseUnits(LP_ALLOCATION),
+    ecosystemTokens: baseUnits(VAULT_ALLOCATION),
+    decimals: TOKEN_DECIMALS,
+  };
+  // No whitelist-only window, tiers unused
+  const noWhitelistWindow = {
+    whitelistStartTs: new anchor.BN(0),
+    whitelistEndTs: new anchor.BN(0),
+    tiers: [1, 2, 3].map(() => ({ discountBps: 0, maxContributionLamports: new anchor.BN(0) })),
+  };
+
+  // Local stand-in for pump.fun (programs/mock-pumpfun)
+  const MOCK_PUMPFUN_PROGRAM_ID = new PublicKey("7uwzfMVNFswtXjq55ha1L2fAqjWPVa2aTDzcLWRjVXBD");
+  // Local stand-in for PumpSwap (programs/mock-amm)
+  const MOCK_AMM_PROGRAM_ID = new PublicKey("3BRYigbjj6tFKFnBRDTR5ynUiE7N1wPkZdTg8yj2zq2w");
+
+  // LP tokens unlockable right after migration, to the presale authority
+  const noLpLock = { lockSeconds: new anchor.BN(0), recipient: PublicKey.default };
+  // withdraw_for_launch pays out the whole raise at once
+  const noEscrow = { launchTrancheBps: 0, releaseDelaySeconds: new anchor.BN(0) };
+  // No quorum, plain yes > no
+  // VIP contributions weigh the same as public ones
+  const simpleMajority = {
+    quorumBps: 0,
+    approvalBps: 0,
+    defaultOutcome: 2,
+    vipWeightBps: 10_000,
+    quadratic: false,
+  };
+  // Main presale: LP locked for an hour, then released to the treasury
+  const LP_LOCK_SECONDS = 3600;
+
+  // Everything claimable at migration
+  const noVesting = {
+    tgeUnlockBps: 0,
+    cliffSeconds: new anchor.BN(0),
+    durationSeconds: new anchor.BN(0),
+    stepSeconds: new anchor.BN(0),
+  };
 
   before(async () => {
     // Initialize test keypairs
@@ -78,12 +128,39 @@ describe("onlypump_presale", () => {
       TOKEN_DECIMALS
     );
 
+    // The token exists with its supply before the presale's vaults are created
+    const authorityTokenAccount = await getAssociatedTokenAddress(tokenMint, authority.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          authorityTokenAccount,
+          authority.publicKey,
+          tokenMint
+        )
+      ),
+      [authority]
+    );
+    await mintTo(
+      provider.connection,
+      authority,
+      tokenMint,
+      authorityTokenAccount,
+      authority,
+      BigInt(TOTAL_PRESALE_TOKENS * 10 ** TOKEN_DECIMALS)
+    );
+
     // Derive PDAs
     [platformConfig] = PublicKey.findProgramAddressSync(
       [Buffer.from("platform")],
       program.programId
     );
 
+    [feeVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_vault")],
+      program.programId
+    );
+
     [presale] = PublicKey.findProgramAddressSync(
       [Buffer.from("presale"), tokenMint.toBuffer()],
       program.programId
@@ -112,6 +189,221 @@ describe("onlypump_presale", () => {
     ecosystemVaultAuthority = ecosystemVault; // Same PDA
   });
 
+  // Creates a fresh mint + presale with the default allocation; `overrides` patch the params
+  // `reservedMint` is a mint address that doesn't exist yet (e.g. for a pump.fun launch)
+  async function createTestPresale(overrides: Record<string, any> = {}, reservedMint?: PublicKey) {
+    const mint =
+      reservedMint ??
+      (await createMint(provider.connection, authority, authority.publicKey, null, TOKEN_DECIMALS));
+    const [presalePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("presale"), mint.toBuffer()],
+      program.programId
+    );
+    const [solVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("public_sol_vault"), presalePda.toBuffer()],
+      program.programId
+    );
+    const now = Math.floor(Date.now() / 1000);
+
+    const params = {
+      publicStartTs: new anchor.BN(now),
+      publicEndTs: new anchor.BN(now + 3600),
+      ...noWhitelistWindow,
+      publicPriceLamportsPerToken: new anchor.BN(1_000_000),
+      hardCapLamports: new anchor.BN(400 * LAMPORTS_PER_SOL),
+      softCapLamports: new anchor.BN(0),
+      minContributionLamports: new anchor.BN(0),
+      maxContributionPerWalletLamports: new anchor.BN(0),
+      timeChecksDisabled: true,
+      allocation: defaultAllocation,
+      vesting: noVesting,
+      claimMode: { fixedPrice: {} },
+      lpLock: noLpLock,
+      escrow: noEscrow,
+      creatorBondLamports: new anchor.BN(0),
+      voteConfig: simpleMajority,
+      ...overrides,
+    };
+
+    // A bond is posted by the presale authority
+    const bonded = overrides.creatorBondLamports?.gtn(0) ?? false;
+    await anyProgram.methods
+      .createPresale(mint, authority.publicKey, params)
+      .accounts({ admin: owner.publicKey, creator: bonded ? authority.publicKey : null, mint })
+      .signers(bonded ? [owner, authority] : [owner])
+      .rpc();
+
+    return { mint, presale: presalePda, publicSolVault: solVault };
+  }
+
+  // Finalize a presale `user` contributed to and have `user` vote it to Launch (~12s)
+  async function finalizeWithLaunchVote(presalePda: PublicKey) {
+    await anyProgram.methods
+      .startVote(new anchor.BN(Math.floor(Date.now() / 1000) + 10))
+      .accounts({ platform: platformConfig, presale: presalePda, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .castVote(true)
+      .accounts({
+        presale: presalePda,
+        userPosition: positionPda(presalePda, user.publicKey),
+        voteReceipt: voteReceiptPda(presalePda, 1, user.publicKey),
+        vipPool: null,
+        vipPosition: null,
+        voter: user.publicKey,
+      })
+      .signers([user])
+      .rpc();
+    await anyProgram.methods
+      .finalizePresale()
+      .accounts({ presale: presalePda, caller: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 12_000));
+    await anyProgram.methods.resolveVote().accounts({ presale: presalePda }).rpc();
+  }
+
+  // Mint the full default allocation to the authority, create the token vaults of a presale
+  // that passed its launch vote (they need the supply to exist), deposit it all and seal it
+  async function fundAndSealVaults(presalePda: PublicKey, mint: PublicKey) {
+    const authorityAta = await getAssociatedTokenAddress(mint, authority.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(authority.publicKey, authorityAta, authority.publicKey, mint)
+      ),
+      [authority]
+    );
+    await mintTo(
+      provider.connection,
+      authority,
+      mint,
+      authorityAta,
+      authority,
+      BigInt(baseUnits(TOTAL_PRESALE_TOKENS).toString())
+    );
+    await anyProgram.methods
+      .initializeVaults()
+      .accounts({ presale: presalePda, admin: owner.publicKey, mint })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .fundPresaleTokens(baseUnits(TOTAL_PRESALE_TOKENS))
+      .accounts({ presale: presalePda, fromTokenAccount: authorityAta, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+    await anyProgram.methods
+      .sealFunding(baseUnits(PRESALE_ALLOCATION), baseUnits(LP_ALLOCATION), baseUnits(VAULT_ALLOCATION))
+      .accounts({ presale: presalePda, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+  }
+
+  // lp_authority and the PumpSwap pool accounts (passed as remaining accounts) for
+  // migrate_and_create_lp against the mock AMM
+  function mockAmmPool(presalePda: PublicKey, mint: PublicKey) {
+    const [lpAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_authority"), presalePda.toBuffer()],
+      program.programId
+    );
+    const poolIndex = Buffer.alloc(2); // index 0, u16 LE
+    const [pool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), poolIndex, lpAuthority.toBuffer(), mint.toBuffer(), NATIVE_MINT.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const [lpMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_lp_mint"), pool.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const lpTokenAccount = getAssociatedTokenAddressSync(lpMint, lpAuthority, true);
+    const writable = (pubkey: PublicKey) => ({ pubkey, isSigner: false, isWritable: true });
+    const readonly = (pubkey: PublicKey) => ({ pubkey, isSigner: false, isWritable: false });
+    return {
+      lpAuthority,
+      lpMint,
+      lpTokenAccount,
+      remainingAccounts: [
+        writable(pool),
+        readonly(Keypair.generate().publicKey), // global_config, ignored by the mock
+        writable(lpMint),
+        writable(lpTokenAccount),
+        writable(getAssociatedTokenAddressSync(mint, pool, true)),
+        writable(getAssociatedTokenAddressSync(NATIVE_MINT, pool, true)),
+        readonly(TOKEN_2022_PROGRAM_ID),
+        readonly(Keypair.generate().publicKey), // event_authority, ignored by the mock
+      ],
+    };
+  }
+
+  // Raydium CPMM pool accounts (remaining accounts, in the adapter's order) for
+  // migrate_and_create_lp against the mock AMM; CPMM orders the pair by mint address
+  function mockCpmmPool(presalePda: PublicKey, mint: PublicKey) {
+    const [lpAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_authority"), presalePda.toBuffer()],
+      program.programId
+    );
+    const [token0, token1] =
+      Buffer.compare(mint.toBuffer(), NATIVE_MINT.toBuffer()) < 0 ? [mint, NATIVE_MINT] : [NATIVE_MINT, mint];
+    const ammConfig = Keypair.generate().publicKey;
+    const [authorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_and_lp_mint_auth_seed")],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const [poolState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), ammConfig.toBuffer(), token0.toBuffer(), token1.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const [lpMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_lp_mint"), poolState.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const vault = (vaultMint: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("pool_vault"), poolState.toBuffer(), vaultMint.toBuffer()],
+        MOCK_AMM_PROGRAM_ID
+      )[0];
+    const lpTokenAccount = getAssociatedTokenAddressSync(lpMint, lpAuthority, true);
+    const writable = (pubkey: PublicKey) => ({ pubkey, isSigner: false, isWritable: true });
+    const readonly = (pubkey: PublicKey) => ({ pubkey, isSigner: false, isWritable: false });
+    return {
+      lpAuthority,
+      lpMint,
+      lpTokenAccount,
+      baseVault: vault(mint),
+      quoteVault: vault(NATIVE_MINT),
+      remainingAccounts: [
+        readonly(ammConfig),
+        readonly(authorityPda),
+        writable(poolState),
+        writable(lpMint),
+        writable(lpTokenAccount),
+        writable(vault(token0)),
+        writable(vault(token1)),
+        writable(Keypair.generate().publicKey), // create_pool_fee, ignored by the mock
+        writable(Keypair.generate().publicKey), // observation_state, ignored by the mock
+      ],
+    };
+  }
+
+  // Pool creation budget lent to lp_authority (rent for the mock pool's accounts)
+  const POOL_CREATION_LAMPORTS = new anchor.BN(0.05 * LAMPORTS_PER_SOL);
+
+  function positionPda(presalePda: PublicKey, wallet: PublicKey) {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), presalePda.toBuffer(), wallet.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function voteReceiptPda(presalePda: PublicKey, round: number, voter: PublicKey) {
+    const roundBytes = Buffer.alloc(4);
+    roundBytes.writeUInt32LE(round);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("vote"), presalePda.toBuffer(), roundBytes, voter.toBuffer()],
+      program.programId
+    )[0];
+  }
+
   it("Initializes the platform", async () => {
     const feeBps = 100; // 1% fee
 
@@ -132,6 +424,97 @@ describe("onlypump_presale", () => {
     expect(platform.operator.toString()).to.equal(authority.publicKey.toString());
     expect(platform.treasury.toString()).to.equal(treasury.publicKey.toString());
     expect(platform.feeBps).to.equal(feeBps);
+
+    // LP creation defaults to mainnet PumpSwap; point it at the mock AMM
+    expect(platform.pumpswapProgram.toString()).to.equal("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA");
+    await anyProgram.methods
+      .updatePlatformConfig({ operator: null, treasury: null, feeBps: null, pumpswapProgram: MOCK_AMM_PROGRAM_ID })
+      .accounts({ owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+  });
+
+  it("Updates platform config and transfers ownership in two steps", async () => {
+    // Operator cannot touch platform config
+    try {
+      await anyProgram.methods
+        .updatePlatformConfig({ operator: null, treasury: null, feeBps: 200 })
+        .accounts({ owner: authority.publicKey })
+        .signers([authority])
+        .rpc();
+      expect.fail("operator should not be able to update config");
+    } catch (error: any) {
+      expect(error.toString()).to.include("Unauthorized");
+    }
+
+    // Fee is bounded
+    try {
+      await anyProgram.methods
+        .updatePlatformConfig({ operator: null, treasury: null, feeBps: 5_000 })
+        .accounts({ owner: owner.publicKey })
+        .signers([owner])
+        .rpc();
+      expect.fail("fee above the maximum should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("FeeTooHigh");
+    }
+
+    await anyProgram.methods
+      .updatePlatformConfig({ operator: null, treasury: null, feeBps: 150 })
+      .accounts({ owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    let platform: any = await program.account.platformConfig.fetch(platformConfig);
+    expect(platform.feeBps).to.equal(150);
+    expect(platform.operator.toString()).to.equal(authority.publicKey.toString());
+
+    // Hand ownership to a new key and back again
+    const newOwner = Keypair.generate();
+    await anyProgram.methods
+      .proposeOwner(newOwner.publicKey)
+      .accounts({ owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    try {
+      await anyProgram.methods
+        .acceptOwner()
+        .accounts({ newOwner: user.publicKey })
+        .signers([user])
+        .rpc();
+      expect.fail("only the pending owner can accept");
+    } catch (error: any) {
+      expect(error.toString()).to.include("Unauthorized");
+    }
+
+    await anyProgram.methods
+      .acceptOwner()
+      .accounts({ newOwner: newOwner.publicKey })
+      .signers([newOwner])
+      .rpc();
+    platform = await program.account.platformConfig.fetch(platformConfig);
+    expect(platform.owner.toString()).to.equal(newOwner.publicKey.toString());
+
+    await anyProgram.methods
+      .proposeOwner(owner.publicKey)
+      .accounts({ owner: newOwner.publicKey })
+      .signers([newOwner])
+      .rpc();
+    await anyProgram.methods
+      .acceptOwner()
+      .accounts({ newOwner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    // Restore the original fee so later fee assertions hold
+    await anyProgram.methods
+      .updatePlatformConfig({ operator: null, treasury: null, feeBps: 100 })
+      .accounts({ owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    platform = await program.account.platformConfig.fetch(platformConfig);
+    expect(platform.owner.toString()).to.equal(owner.publicKey.toString());
+    expect(platform.pendingOwner.toString()).to.equal(PublicKey.default.toString());
   });
 
   it("Creates a presale", async () => {
@@ -146,16 +529,28 @@ describe("onlypump_presale", () => {
     let tx: string;
     try {
       tx = await anyProgram.methods
-        .createPresale(
-          tokenMint,
-          authority.publicKey,
+        .createPresale(tokenMint, authority.publicKey, {
           publicStartTs,
           publicEndTs,
+          ...noWhitelistWindow,
           publicPriceLamportsPerToken,
-          hardCapLamports
-        )
+          hardCapLamports,
+          softCapLamports: new anchor.BN(0),
+          minContributionLamports: new anchor.BN(0),
+          maxContributionPerWalletLamports: new anchor.BN(0),
+          // The suite contributes/finalizes immediately, so skip window enforcement
+          timeChecksDisabled: true,
+          allocation: defaultAllocation,
+          vesting: noVesting,
+          claimMode: { fixedPrice: {} },
+          lpLock: { lockSeconds: new anchor.BN(LP_LOCK_SECONDS), recipient: treasury.publicKey },
+          escrow: noEscrow,
+          creatorBondLamports: new anchor.BN(0),
+          voteConfig: simpleMajority,
+        })
         .accounts({
           admin: owner.publicKey,
+          creator: null,
           mint: tokenMint,
         })
         .signers([owner])
@@ -197,60 +592,7 @@ describe("onlypump_presale", () => {
     );
     expect(presaleAccount.isFinalized).to.be.false;
     expect(presaleAccount.isMigrated).to.be.false;
-  });
-
-  it("Funds presale tokens", async () => {
-    // Create authority's token account first
-    const authorityTokenAccount = await getAssociatedTokenAddress(
-      tokenMint,
-      authority.publicKey
-    );
-
-    // Create the token account if it doesn't exist
-    try {
-      await getAccount(provider.connection, authorityTokenAccount);
-    } catch {
-      // Account doesn't exist, create it
-      const createATA = await provider.sendAndConfirm(
-        new anchor.web3.Transaction().add(
-          createAssociatedTokenAccountInstruction(
-            authority.publicKey,
-            authorityTokenAccount,
-            authority.publicKey,
-            tokenMint
-          )
-        ),
-        [authority]
-      );
-    }
-
-    // Mint 800M tokens to authority
-    const mintAmount = BigInt(TOTAL_PRESALE_TOKENS * 10 ** TOKEN_DECIMALS);
-    await mintTo(
-      provider.connection,
-      authority,
-      tokenMint,
-      authorityTokenAccount,
-      authority,
-      mintAmount
-    );
-
-    // Fund presale
-    const tx = await anyProgram.methods
-      .fundPresaleTokens(new anchor.BN(mintAmount.toString()))
-      .accounts({
-        presale: presale, // Provide presale so Anchor can derive token_vault
-        fromTokenAccount: authorityTokenAccount,
-        authority: authority.publicKey,
-      })
-      .signers([authority])
-      .rpc();
-
-    console.log("Fund presale tokens tx:", tx);
-
-    // Verify token vault has the tokens
-    const vaultAccount = await getAccount(provider.connection, tokenVault);
-    expect(vaultAccount.amount.toString()).to.equal(mintAmount.toString());
+    expect((presaleAccount as any).phase).to.deep.equal({ publicActive: {} });
   });
 
   it("Whitelists a user", async () => {
@@ -299,7 +641,7 @@ describe("onlypump_presale", () => {
     const contributionAmount = new anchor.BN(1 * LAMPORTS_PER_SOL); // 1 SOL
 
     const tx = await anyProgram.methods
-      .contributePublic(contributionAmount)
+      .contributePublic(contributionAmount, false, null)
       .accounts({
         presale: presale, // Provide presale so Anchor can derive publicSolVault and userPosition
         user: user.publicKey,
@@ -324,116 +666,768 @@ describe("onlypump_presale", () => {
     expect(position.tokensAllocated.toNumber()).to.be.greaterThan(0);
   });
 
-  it("Finalizes the presale", async () => {
-    // In a real test, you'd wait for the end time or use clockwork
-    // For now, we'll call it directly (assuming admin can finalize early in dev)
+  it("Ignores a tier-0 whitelist entry in the public window", async () => {
+    const open = await createTestPresale();
+    await anyProgram.methods
+      .whitelistUser(0, new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+      .accounts({ presale: open.presale, admin: owner.publicKey, user: user.publicKey })
+      .signers([owner])
+      .rpc();
 
-    const tx = await anyProgram.methods
-      .finalizePresale()
+    // Neither rejected as "not whitelisted" nor held to the entry's 0.1 SOL cap
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(0.5 * LAMPORTS_PER_SOL), false, null)
       .accounts({
-        presale: presale, // Provide presale explicitly
-        admin: owner.publicKey,
+        presale: open.presale,
+        user: user.publicKey,
+        whitelist: PublicKey.findProgramAddressSync(
+          [Buffer.from("whitelist"), open.presale.toBuffer(), user.publicKey.toBuffer()],
+          program.programId
+        )[0],
       })
-      .signers([owner])
+      .signers([user])
       .rpc();
+    const position = await program.account.userPosition.fetch(positionPda(open.presale, user.publicKey));
+    expect(position.publicContributionLamports.toString()).to.equal((0.5 * LAMPORTS_PER_SOL).toString());
+  });
 
-    console.log("Finalize presale tx:", tx);
+  it("Enforces the global cap with optional partial fills", async () => {
+    // 2 SOL hard cap, but only 1500 public tokens (= 1.5 SOL at 0.001 SOL/token)
+    const capped = await createTestPresale({
+      hardCapLamports: new anchor.BN(2 * LAMPORTS_PER_SOL),
+      allocation: { ...defaultAllocation, publicTokens: baseUnits(1_500) },
+    });
 
-    const presaleAccount = await program.account.presale.fetch(presale);
-    expect(presaleAccount.isFinalized).to.be.true;
-  });
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: capped.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
 
-  it("Migrates presale and creates LP (stub)", async () => {
-    // LP token account should be a regular token account (not PDA)
-    // Use treasury's ATA for simplicity
-    const lpTokenAccount = await getAssociatedTokenAddress(tokenMint, treasury.publicKey);
-    
-    // Create the ATA if it doesn't exist
+    // 1 SOL more would overshoot the token cap
     try {
-      await getAccount(provider.connection, lpTokenAccount);
-    } catch {
-      const createATA = await provider.sendAndConfirm(
-        new anchor.web3.Transaction().add(
-          createAssociatedTokenAccountInstruction(
-            treasury.publicKey,
-            lpTokenAccount,
-            treasury.publicKey,
-            tokenMint
-          )
-        ),
-        [treasury]
-      );
+      await anyProgram.methods
+        .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+        .accounts({ presale: capped.presale, user: user.publicKey, whitelist: null })
+        .signers([user])
+        .rpc();
+      expect.fail("contribution over the token cap should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("TokenCapExceeded");
     }
-    
-    const lpSolAccount = Keypair.generate();
 
-    // Airdrop to LP SOL account
-    await provider.connection.requestAirdrop(
-      lpSolAccount.publicKey,
-      1 * LAMPORTS_PER_SOL
+    // With partial fill only the 0.5 SOL that still fits is taken
+    const vaultBefore = await provider.connection.getBalance(capped.publicSolVault);
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), true, null)
+      .accounts({ presale: capped.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+    const vaultAfter = await provider.connection.getBalance(capped.publicSolVault);
+    expect(vaultAfter - vaultBefore).to.equal(0.5 * LAMPORTS_PER_SOL);
+
+    const cappedAccount: any = await program.account.presale.fetch(capped.presale);
+    expect(cappedAccount.totalTokensAllocated.toString()).to.equal(
+      baseUnits(1_500).toString()
     );
-    await new Promise((resolve) => setTimeout(resolve, 1000));
+    expect(cappedAccount.publicRaisedLamports.toString()).to.equal(
+      (1.5 * LAMPORTS_PER_SOL).toString()
+    );
+  });
 
-    const lpSolAmount = new anchor.BN(0.5 * LAMPORTS_PER_SOL); // 0.5 SOL for LP
+  it("Enforces minimum and cumulative per-wallet contribution limits", async () => {
+    const limited = await createTestPresale({
+      minContributionLamports: new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+      maxContributionPerWalletLamports: new anchor.BN(1 * LAMPORTS_PER_SOL),
+    });
+    const contribute = (lamports: number) =>
+      anyProgram.methods
+        .contributePublic(new anchor.BN(lamports), false, null)
+        .accounts({ presale: limited.presale, user: user.publicKey, whitelist: null })
+        .signers([user])
+        .rpc();
 
-    const tx = await anyProgram.methods
-      .migrateAndCreateLp(lpSolAmount)
-      .accounts({
-        presale: presale, // Provide presale so Anchor can derive token_vault, ecosystem_vault, etc.
-        lpTokenAccount: lpTokenAccount,
-        lpSolAccount: lpSolAccount.publicKey,
-        treasury: treasury.publicKey,
-        admin: owner.publicKey,
-      })
-      .signers([owner])
-      .rpc();
+    try {
+      await contribute(0.05 * LAMPORTS_PER_SOL);
+      expect.fail("contribution below the minimum should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("ContributionBelowMinimum");
+    }
 
-    console.log("Migrate and create LP tx:", tx);
+    await contribute(0.6 * LAMPORTS_PER_SOL);
 
-    const presaleAccount = await program.account.presale.fetch(presale);
-    expect(presaleAccount.isMigrated).to.be.true;
+    // Second call is within the single-call limit but over the cumulative wallet cap
+    try {
+      await contribute(0.6 * LAMPORTS_PER_SOL);
+      expect.fail("cumulative contribution over the wallet cap should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("WalletCapExceeded");
+    }
 
-    // Verify ecosystem vault received tokens
-    const ecosystemAccount = await getAccount(provider.connection, ecosystemVault);
-    expect(ecosystemAccount.amount.toString()).to.equal(
-      (VAULT_ALLOCATION * 10 ** TOKEN_DECIMALS).toString()
+    await contribute(0.4 * LAMPORTS_PER_SOL);
+    const position = await program.account.userPosition.fetch(
+      positionPda(limited.presale, user.publicKey)
     );
-  });
-
-  it("User claims tokens", async () => {
-    const [userPosition] = PublicKey.findProgramAddressSync(
-      [Buffer.from("position"), presale.toBuffer(), user.publicKey.toBuffer()],
-      program.programId
+    expect(position.publicContributionLamports.toString()).to.equal(
+      (1 * LAMPORTS_PER_SOL).toString()
     );
 
-    const userTokenAccount = await getAssociatedTokenAddress(
-      tokenMint,
-      user.publicKey
+    // The minimum applies to what a partial fill accepts, not to what was requested
+    const nearlyFull = await createTestPresale({
+      minContributionLamports: new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+      hardCapLamports: new anchor.BN(1.05 * LAMPORTS_PER_SOL),
+    });
+    const contributeNearlyFull = (lamports: number) =>
+      anyProgram.methods
+        .contributePublic(new anchor.BN(lamports), true, null)
+        .accounts({ presale: nearlyFull.presale, user: user.publicKey, whitelist: null })
+        .signers([user])
+        .rpc();
+    await contributeNearlyFull(LAMPORTS_PER_SOL);
+    try {
+      await contributeNearlyFull(0.5 * LAMPORTS_PER_SOL);
+      expect.fail("a 0.05 SOL partial fill is below the minimum");
+    } catch (error: any) {
+      expect(error.toString()).to.include("ContributionBelowMinimum");
+    }
+
+    // Likewise the wallet cap: 0.7 + 0.5 requested is over 1 SOL, but the 0.3 SOL fill is not
+    const walletCapped = await createTestPresale({
+      hardCapLamports: new anchor.BN(LAMPORTS_PER_SOL),
+      maxContributionPerWalletLamports: new anchor.BN(LAMPORTS_PER_SOL),
+    });
+    for (const lamports of [0.7 * LAMPORTS_PER_SOL, 0.5 * LAMPORTS_PER_SOL]) {
+      await anyProgram.methods
+        .contributePublic(new anchor.BN(lamports), true, null)
+        .accounts({ presale: walletCapped.presale, user: user.publicKey, whitelist: null })
+        .signers([user])
+        .rpc();
+    }
+    const walletCappedPosition = await program.account.userPosition.fetch(
+      positionPda(walletCapped.presale, user.publicKey)
+    );
+    expect(walletCappedPosition.publicContributionLamports.toString()).to.equal(
+      LAMPORTS_PER_SOL.toString()
     );
+  });
 
-    // Ensure the user's token account exists (create ATA if needed)
+  it("Rejects contributions outside the public window when time checks are enabled", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const contribute = (presalePda: PublicKey) =>
+      anyProgram.methods
+        .contributePublic(new anchor.BN(0.1 * LAMPORTS_PER_SOL), false, null)
+        .accounts({ presale: presalePda, user: user.publicKey, whitelist: null })
+        .signers([user])
+        .rpc();
+
+    // Before public_start_ts
+    const upcoming = await createTestPresale({
+      publicStartTs: new anchor.BN(now + 3600),
+      publicEndTs: new anchor.BN(now + 7200),
+      timeChecksDisabled: false,
+    });
     try {
-      await getAccount(provider.connection, userTokenAccount);
-    } catch {
-      const createATA = await provider.sendAndConfirm(
-        new anchor.web3.Transaction().add(
-          createAssociatedTokenAccountInstruction(
-            user.publicKey, // payer
-            userTokenAccount,
-            user.publicKey, // owner
-            tokenMint
-          )
-        ),
-        [user]
-      );
+      await contribute(upcoming.presale);
+      expect.fail("contributing before public_start_ts should fail");
+    } catch (error: any) {
+      expect(error.toString()).to.include("PresaleNotActive");
     }
 
-    const positionBefore = await program.account.userPosition.fetch(userPosition);
-    const tokensToClaim = positionBefore.tokensAllocated.sub(positionBefore.tokensClaimed);
+    // Open now, closed after public_end_ts
+    const publicEndTs = now + 5;
+    const closing = await createTestPresale({
+      publicStartTs: new anchor.BN(now - 10),
+      publicEndTs: new anchor.BN(publicEndTs),
+      timeChecksDisabled: false,
+    });
+    await contribute(closing.presale);
+    await new Promise((resolve) => setTimeout(resolve, Math.max(0, (publicEndTs + 2) * 1000 - Date.now())));
+    try {
+      await contribute(closing.presale);
+      expect.fail("contributing after public_end_ts should fail");
+    } catch (error: any) {
+      expect(error.toString()).to.include("PresaleNotActive");
+    }
+  });
 
-    const tx = await program.methods
-      .claimTokens()
-      .accounts({
+  it("Opens a whitelist-only window with tier pricing and caps", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const tiered = await createTestPresale({
+      whitelistStartTs: new anchor.BN(now - 10),
+      whitelistEndTs: new anchor.BN(now + 3600),
+      publicStartTs: new anchor.BN(now + 3600),
+      publicEndTs: new anchor.BN(now + 7200),
+      timeChecksDisabled: false,
+      tiers: [
+        // Tier 1: 20% off, 0.5 SOL per wallet
+        { discountBps: 2_000, maxContributionLamports: new anchor.BN(0.5 * LAMPORTS_PER_SOL) },
+        { discountBps: 0, maxContributionLamports: new anchor.BN(0) },
+        { discountBps: 0, maxContributionLamports: new anchor.BN(0) },
+      ],
+    });
+
+    // A wallet without a whitelist entry is locked out of the whitelist window
+    const outsider = Keypair.generate();
+    await provider.connection.requestAirdrop(outsider.publicKey, LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    try {
+      await anyProgram.methods
+        .contributePublic(new anchor.BN(0.1 * LAMPORTS_PER_SOL), false, null)
+        .accounts({ presale: tiered.presale, user: outsider.publicKey, whitelist: null })
+        .signers([outsider])
+        .rpc();
+      expect.fail("non-whitelisted wallet should be rejected in the whitelist window");
+    } catch (error: any) {
+      expect(error.toString()).to.include("NotWhitelisted");
+    }
+
+    const [tieredWhitelist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("whitelist"), tiered.presale.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    await anyProgram.methods
+      .whitelistUser(1, new anchor.BN(0))
+      .accounts({ presale: tiered.presale, admin: owner.publicKey, user: user.publicKey })
+      .signers([owner])
+      .rpc();
+
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(0.5 * LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: tiered.presale, user: user.publicKey, whitelist: tieredWhitelist })
+      .signers([user])
+      .rpc();
+
+    // 0.5 SOL at 0.0008 SOL/token (20% off 0.001) = 625 tokens
+    const position = await program.account.userPosition.fetch(
+      positionPda(tiered.presale, user.publicKey)
+    );
+    expect(position.tokensAllocated.toString()).to.equal(baseUnits(625).toString());
+
+    try {
+      await anyProgram.methods
+        .contributePublic(new anchor.BN(0.1 * LAMPORTS_PER_SOL), false, null)
+        .accounts({ presale: tiered.presale, user: user.publicKey, whitelist: tieredWhitelist })
+        .signers([user])
+        .rpc();
+      expect.fail("contribution over the tier cap should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("TierCapExceeded");
+    }
+  });
+
+  it("Accepts Merkle allowlist proofs instead of WhitelistEntry accounts", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const merkle = await createTestPresale({
+      whitelistStartTs: new anchor.BN(now - 10),
+      whitelistEndTs: new anchor.BN(now + 3600),
+      publicStartTs: new anchor.BN(now + 3600),
+      publicEndTs: new anchor.BN(now + 7200),
+      timeChecksDisabled: false,
+      tiers: [
+        { discountBps: 0, maxContributionLamports: new anchor.BN(0) },
+        // Tier 2: 10% off
+        { discountBps: 1_000, maxContributionLamports: new anchor.BN(0) },
+        { discountBps: 0, maxContributionLamports: new anchor.BN(0) },
+      ],
+    });
+
+    // leaf = keccak256(user || pool || tier || max_contribution_lamports LE), sorted-pair tree
+    const PUBLIC_POOL = 0;
+    const VIP_POOL = 1;
+    const leaf = (wallet: PublicKey, pool: number, tier: number, max: anchor.BN) =>
+      Buffer.from(
+        keccak_256(
+          Buffer.concat([wallet.toBuffer(), Buffer.from([pool, tier]), max.toArrayLike(Buffer, "le", 8)])
+        )
+      );
+    const hashPair = (a: Buffer, b: Buffer) =>
+      Buffer.from(keccak_256(Buffer.compare(a, b) <= 0 ? Buffer.concat([a, b]) : Buffer.concat([b, a])));
+
+    const maxContribution = new anchor.BN(2 * LAMPORTS_PER_SOL);
+    const userLeaf = leaf(user.publicKey, PUBLIC_POOL, 2, maxContribution);
+    const userVipLeaf = leaf(user.publicKey, VIP_POOL, 2, maxContribution);
+    const otherLeafA = leaf(Keypair.generate().publicKey, PUBLIC_POOL, 2, maxContribution);
+    const otherLeafB = leaf(Keypair.generate().publicKey, PUBLIC_POOL, 2, maxContribution);
+    const root = hashPair(hashPair(userLeaf, otherLeafA), hashPair(userVipLeaf, otherLeafB));
+    const publicProof = [otherLeafA, hashPair(userVipLeaf, otherLeafB)];
+    const vipProof = [otherLeafB, hashPair(userLeaf, otherLeafA)];
+
+    await anyProgram.methods
+      .setWhitelistRoot(2, Array.from(root))
+      .accounts({ presale: merkle.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    const contributeWithProof = (max: anchor.BN, proof: Buffer[]) =>
+      anyProgram.methods
+        .contributePublic(new anchor.BN(0.9 * LAMPORTS_PER_SOL), false, {
+          merkle: { tier: 2, maxContributionLamports: max, proof: proof.map((node) => Array.from(node)) },
+        })
+        .accounts({ presale: merkle.presale, user: user.publicKey, whitelist: null })
+        .signers([user])
+        .rpc();
+
+    // Claiming a bigger cap than the one in the tree fails verification
+    try {
+      await contributeWithProof(new anchor.BN(100 * LAMPORTS_PER_SOL), publicProof);
+      expect.fail("proof for a different leaf should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("InvalidMerkleProof");
+    }
+
+    await contributeWithProof(maxContribution, publicProof);
+
+    // 0.9 SOL at 0.0009 SOL/token (10% off) = 1000 tokens
+    const position = await program.account.userPosition.fetch(
+      positionPda(merkle.presale, user.publicKey)
+    );
+    expect(position.tokensAllocated.toString()).to.equal(baseUnits(1_000).toString());
+
+    // Leaves are bound to a pool: the public grant doesn't open the VIP round
+    await anyProgram.methods
+      .createVipPool(new anchor.BN(500_000), new anchor.BN(2 * LAMPORTS_PER_SOL))
+      .accounts({ presale: merkle.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    const contributeVipWithProof = (proof: Buffer[]) =>
+      anyProgram.methods
+        .contributeVip(new anchor.BN(0.5 * LAMPORTS_PER_SOL), {
+          merkle: { tier: 2, maxContributionLamports: maxContribution, proof: proof.map((node) => Array.from(node)) },
+        })
+        .accounts({ presale: merkle.presale, user: user.publicKey, whitelist: null })
+        .signers([user])
+        .rpc();
+    try {
+      await contributeVipWithProof(publicProof);
+      expect.fail("a public-pool leaf should not verify in the VIP round");
+    } catch (error: any) {
+      expect(error.toString()).to.include("InvalidMerkleProof");
+    }
+    await contributeVipWithProof(vipProof);
+    const presaleAccount: any = await program.account.presale.fetch(merkle.presale);
+    expect(presaleAccount.vipRaisedLamports.toString()).to.equal((0.5 * LAMPORTS_PER_SOL).toString());
+  });
+
+  it("Accepts backend-signed contribution vouchers", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const voucherSigner = Keypair.generate();
+    await anyProgram.methods
+      .updatePlatformConfig({ operator: null, treasury: null, feeBps: null, voucherSigner: voucherSigner.publicKey })
+      .accounts({ owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    const vouchered = await createTestPresale({
+      whitelistStartTs: new anchor.BN(now - 10),
+      whitelistEndTs: new anchor.BN(now + 3600),
+      publicStartTs: new anchor.BN(now + 3600),
+      publicEndTs: new anchor.BN(now + 7200),
+      timeChecksDisabled: false,
+    });
+
+    const voucher = (nonce: number, pool: "public" | "vip" = "public") => ({
+      presale: vouchered.presale,
+      user: user.publicKey,
+      pool: { [pool]: {} },
+      tier: 1,
+      maxContributionLamports: new anchor.BN(2 * LAMPORTS_PER_SOL),
+      expiryTs: new anchor.BN(now + 600),
+      nonce: new anchor.BN(nonce),
+    });
+    // Borsh layout of ContributionVoucher
+    const voucherMessage = (v: ReturnType<typeof voucher>) =>
+      Buffer.concat([
+        v.presale.toBuffer(),
+        v.user.toBuffer(),
+        Buffer.from(["vip" in v.pool ? 1 : 0, v.tier]),
+        v.maxContributionLamports.toArrayLike(Buffer, "le", 8),
+        v.expiryTs.toTwos(64).toArrayLike(Buffer, "le", 8),
+        v.nonce.toArrayLike(Buffer, "le", 8),
+      ]);
+    const contributeWithVoucher = (v: ReturnType<typeof voucher>, signer: Keypair) =>
+      anyProgram.methods
+        .contributePublic(new anchor.BN(0.5 * LAMPORTS_PER_SOL), false, { voucher: [v] })
+        .accounts({ presale: vouchered.presale, user: user.publicKey, whitelist: null })
+        .preInstructions([
+          Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: signer.secretKey,
+            message: voucherMessage(v),
+          }),
+        ])
+        .signers([user])
+        .rpc();
+
+    // Signed by someone other than the platform's voucher signer
+    try {
+      await contributeWithVoucher(voucher(1), Keypair.generate());
+      expect.fail("voucher from an unknown signer should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("InvalidVoucherSignature");
+    }
+
+    await contributeWithVoucher(voucher(1), voucherSigner);
+
+    // The same voucher cannot be replayed
+    try {
+      await contributeWithVoucher(voucher(1), voucherSigner);
+      expect.fail("voucher nonce should not be reusable");
+    } catch (error: any) {
+      expect(error.toString()).to.include("VoucherNonceReused");
+    }
+
+    await contributeWithVoucher(voucher(2), voucherSigner);
+    const position = await program.account.userPosition.fetch(
+      positionPda(vouchered.presale, user.publicKey)
+    );
+    expect(position.publicContributionLamports.toString()).to.equal(
+      (1 * LAMPORTS_PER_SOL).toString()
+    );
+    expect(position.voucherNonce.toNumber()).to.equal(2);
+
+    // A public-pool voucher can't be replayed in the VIP round (its nonces are tracked separately)
+    await anyProgram.methods
+      .createVipPool(new anchor.BN(500_000), new anchor.BN(2 * LAMPORTS_PER_SOL))
+      .accounts({ presale: vouchered.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    const contributeVipWithVoucher = (v: ReturnType<typeof voucher>) =>
+      anyProgram.methods
+        .contributeVip(new anchor.BN(0.5 * LAMPORTS_PER_SOL), { voucher: [v] })
+        .accounts({ presale: vouchered.presale, user: user.publicKey, whitelist: null })
+        .preInstructions([
+          Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: voucherSigner.secretKey,
+            message: voucherMessage(v),
+          }),
+        ])
+        .signers([user])
+        .rpc();
+    try {
+      await contributeVipWithVoucher(voucher(2));
+      expect.fail("a public voucher should be rejected in the VIP round");
+    } catch (error: any) {
+      expect(error.toString()).to.include("Error Code: InvalidVoucher.");
+    }
+    await contributeVipWithVoucher(voucher(1, "vip"));
+    const [vipPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_pool"), vouchered.presale.toBuffer()],
+      program.programId
+    );
+    const vipPosition: any = await anyProgram.account.vipPosition.fetch(
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("vip_position"), vipPool.toBuffer(), user.publicKey.toBuffer()],
+        program.programId
+      )[0]
+    );
+    expect(vipPosition.voucherNonce.toNumber()).to.equal(1);
+  });
+
+  it("Computes vested amounts from the TGE unlock, cliff and linear schedule", async () => {
+    // 20% at TGE, then 80% over 1000s after a 100s cliff, in 300s steps
+    const vesting = await createTestPresale({
+      vesting: {
+        tgeUnlockBps: 2_000,
+        cliffSeconds: new anchor.BN(100),
+        durationSeconds: new anchor.BN(1_000),
+        stepSeconds: new anchor.BN(300),
+      },
+    });
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(1 * LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: vesting.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+
+    const presaleAccount: any = await program.account.presale.fetch(vesting.presale);
+    const tge = presaleAccount.tgeTs.toNumber();
+    const vestedAt = async (ts: number) =>
+      (
+        await anyProgram.methods
+          .getVestedAmount(new anchor.BN(ts))
+          .accounts({
+            presale: vesting.presale,
+            userPosition: positionPda(vesting.presale, user.publicKey),
+          })
+          .view()
+      ).toString();
+
+    // 1 SOL at 0.001 SOL/token = 1000 tokens
+    expect(await vestedAt(tge - 1)).to.equal("0");
+    expect(await vestedAt(tge)).to.equal(baseUnits(200).toString());
+    expect(await vestedAt(tge + 99)).to.equal(baseUnits(200).toString());
+    // 599s after the cliff rounds down to 300s: 20% + 80% * 0.3
+    expect(await vestedAt(tge + 100 + 599)).to.equal(baseUnits(440).toString());
+    expect(await vestedAt(tge + 100 + 1_000)).to.equal(baseUnits(1_000).toString());
+  });
+
+  it("Finalizes the presale", async () => {
+    // Vaults (and with them funding) need a passed launch vote
+    try {
+      await anyProgram.methods
+        .initializeVaults()
+        .accounts({ presale, admin: owner.publicKey, mint: tokenMint })
+        .signers([owner])
+        .rpc();
+      expect.fail("initialize_vaults before the launch vote should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("PresaleNotFinalized");
+    }
+
+    await finalizeWithLaunchVote(presale);
+
+    const presaleAccount = await program.account.presale.fetch(presale);
+    expect(presaleAccount.isFinalized).to.be.true;
+    expect((presaleAccount as any).phase).to.deep.equal({ launchable: {} });
+
+    await anyProgram.methods
+      .initializeVaults()
+      .accounts({ presale, admin: owner.publicKey, mint: tokenMint })
+      .signers([owner])
+      .rpc();
+  });
+
+  it("Funds presale tokens", async () => {
+    // The authority holds the 800M tokens minted up front
+    const authorityTokenAccount = await getAssociatedTokenAddress(
+      tokenMint,
+      authority.publicKey
+    );
+    const mintAmount = BigInt(TOTAL_PRESALE_TOKENS * 10 ** TOKEN_DECIMALS);
+
+    // Fund presale in two deposits; the split is only fixed by seal_funding
+    const firstDeposit = mintAmount / BigInt(2);
+    for (const deposit of [firstDeposit, mintAmount - firstDeposit]) {
+      const tx = await anyProgram.methods
+        .fundPresaleTokens(new anchor.BN(deposit.toString()))
+        .accounts({
+          presale: presale, // Provide presale so Anchor can derive token_vault
+          fromTokenAccount: authorityTokenAccount,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+      console.log("Fund presale tokens tx:", tx);
+    }
+
+    // Verify token vault has the tokens and the deposits are tracked
+    const vaultAccount = await getAccount(provider.connection, tokenVault);
+    expect(vaultAccount.amount.toString()).to.equal(mintAmount.toString());
+    const presaleAccount: any = await program.account.presale.fetch(presale);
+    expect(presaleAccount.fundedTokens.toString()).to.equal(mintAmount.toString());
+    expect(presaleAccount.fundingSealed).to.be.false;
+  });
+
+  it("Seals funding, migrates into a PumpSwap pool and locks the LP tokens", async () => {
+    // LP token account should be a regular token account (not PDA)
+    const lpSolAmount = new anchor.BN(0.5 * LAMPORTS_PER_SOL); // 0.5 SOL for LP
+
+    // Migration needs sealed funding, and the split must add up to what was deposited
+    const sealFunding = (publicTokens: anchor.BN) =>
+      anyProgram.methods
+        .sealFunding(publicTokens, baseUnits(LP_ALLOCATION), baseUnits(VAULT_ALLOCATION))
+        .accounts({ presale, admin: owner.publicKey })
+        .signers([owner])
+        .rpc();
+    try {
+      await sealFunding(baseUnits(PRESALE_ALLOCATION).subn(1));
+      expect.fail("seal amounts must match the deposits");
+    } catch (error: any) {
+      expect(error.toString()).to.include("FundingAmountMismatch");
+    }
+
+    // Tokens sent straight to the vault (outside fund_presale_tokens) must not block sealing
+    await mintTo(provider.connection, authority, tokenMint, tokenVault, authority, BigInt(1));
+    await sealFunding(baseUnits(PRESALE_ALLOCATION));
+
+    // No more deposits once sealed
+    try {
+      await anyProgram.methods
+        .fundPresaleTokens(new anchor.BN(1))
+        .accounts({
+          presale,
+          fromTokenAccount: await getAssociatedTokenAddress(tokenMint, authority.publicKey),
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+      expect.fail("deposits after sealing should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("FundingSealed");
+    }
+
+    const amm = mockAmmPool(presale, tokenMint);
+    const tx = await anyProgram.methods
+      .migrateAndCreateLp({
+        amm: { pumpSwap: {} },
+        lpSolAmount,
+        poolCreationLamports: POOL_CREATION_LAMPORTS,
+      })
+      .accounts({
+        presale: presale, // Provide presale so Anchor can derive token_vault, ecosystem_vault, etc.
+        mint: tokenMint,
+        quoteMint: NATIVE_MINT,
+        ammProgram: MOCK_AMM_PROGRAM_ID,
+        treasury: treasury.publicKey,
+        // No revenue split or VIP round on this presale
+        influencerConfig: null,
+        creator: null,
+        vipPool: null,
+        vipRewardsVault: null,
+        admin: owner.publicKey,
+      })
+      .remainingAccounts(amm.remainingAccounts)
+      .signers([owner])
+      .rpc();
+
+    console.log("Migrate and create LP tx:", tx);
+
+    const presaleAccount = await program.account.presale.fetch(presale);
+    expect(presaleAccount.isMigrated).to.be.true;
+
+    // The pool holds the LP tokens and 0.5 wrapped SOL; the LP tokens belong to lp_authority
+    const lpTokens = await getAccount(provider.connection, amm.lpTokenAccount);
+    expect(lpTokens.owner.toString()).to.equal(amm.lpAuthority.toString());
+    expect(Number(lpTokens.amount)).to.be.greaterThan(0);
+    const poolBase = await getAccount(
+      provider.connection,
+      amm.remainingAccounts[4].pubkey
+    );
+    expect(poolBase.amount.toString()).to.equal(baseUnits(LP_ALLOCATION).toString());
+    const poolQuote = await getAccount(
+      provider.connection,
+      amm.remainingAccounts[5].pubkey
+    );
+    expect(poolQuote.amount.toString()).to.equal(lpSolAmount.toString());
+    // Unspent pool creation lamports were returned
+    expect(await provider.connection.getBalance(amm.lpAuthority)).to.equal(0);
+
+    // LP tokens are locked for LP_LOCK_SECONDS; burning them is still allowed
+    expect(presaleAccount.lpVault.toString()).to.equal(amm.lpTokenAccount.toString());
+    expect(presaleAccount.lpLockedAmount.toString()).to.equal(lpTokens.amount.toString());
+    expect(presaleAccount.lpUnlockTs.toNumber()).to.be.greaterThan(Math.floor(Date.now() / 1000));
+    const treasuryLpAccount = getAssociatedTokenAddressSync(amm.lpMint, treasury.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          treasury.publicKey,
+          treasuryLpAccount,
+          treasury.publicKey,
+          amm.lpMint
+        )
+      ),
+      [treasury]
+    );
+    try {
+      await anyProgram.methods
+        .unlockLp()
+        .accounts({
+          presale,
+          lpVault: amm.lpTokenAccount,
+          lpMint: amm.lpMint,
+          recipientTokenAccount: treasuryLpAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      expect.fail("unlock before the lock expires should fail");
+    } catch (error: any) {
+      expect(error.toString()).to.include("LpStillLocked");
+    }
+
+    await anyProgram.methods
+      .burnLp()
+      .accounts({
+        presale,
+        lpVault: amm.lpTokenAccount,
+        lpMint: amm.lpMint,
+        admin: owner.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+    expect((await getAccount(provider.connection, amm.lpTokenAccount)).amount.toString()).to.equal("0");
+    const burned: any = await program.account.presale.fetch(presale);
+    expect(burned.lpLockedAmount.toString()).to.equal("0");
+
+    // Verify ecosystem vault received tokens
+    const ecosystemAccount = await getAccount(provider.connection, ecosystemVault);
+    expect(ecosystemAccount.amount.toString()).to.equal(
+      (VAULT_ALLOCATION * 10 ** TOKEN_DECIMALS).toString()
+    );
+  });
+
+  it("Sweeps platform fees to the treasury", async () => {
+    // Migration skimmed fee_bps (1%) of everything leaving the public SOL vault
+    // (1 SOL raised + the vault's rent-exempt lamports) into the fee vault
+    const rentExempt = await provider.connection.getMinimumBalanceForRentExemption(8);
+    const feeVaultBalance = await provider.connection.getBalance(feeVault);
+    const expectedFee = Math.floor(((LAMPORTS_PER_SOL + rentExempt) * 100) / 10_000);
+    expect(feeVaultBalance - rentExempt).to.equal(expectedFee);
+
+    const treasuryBefore = await provider.connection.getBalance(treasury.publicKey);
+
+    // Operator cannot sweep, only the owner
+    try {
+      await anyProgram.methods
+        .sweepFees()
+        .accounts({
+          treasury: treasury.publicKey,
+          owner: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+      expect.fail("operator should not be able to sweep fees");
+    } catch (error: any) {
+      expect(error.toString()).to.include("Unauthorized");
+    }
+
+    await anyProgram.methods
+      .sweepFees()
+      .accounts({
+        treasury: treasury.publicKey,
+        owner: owner.publicKey,
+      })
+      .signers([owner])
+      .rpc();
+
+    const treasuryAfter = await provider.connection.getBalance(treasury.publicKey);
+    expect(treasuryAfter - treasuryBefore).to.equal(expectedFee);
+    expect(await provider.connection.getBalance(feeVault)).to.equal(rentExempt);
+  });
+
+  it("User claims tokens", async () => {
+    const [userPosition] = PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), presale.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const userTokenAccount = await getAssociatedTokenAddress(
+      tokenMint,
+      user.publicKey
+    );
+
+    // Ensure the user's token account exists (create ATA if needed)
+    try {
+      await getAccount(provider.connection, userTokenAccount);
+    } catch {
+      const createATA = await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          createAssociatedTokenAccountInstruction(
+            user.publicKey, // payer
+            userTokenAccount,
+            user.publicKey, // owner
+            tokenMint
+          )
+        ),
+        [user]
+      );
+    }
+
+    const positionBefore = await program.account.userPosition.fetch(userPosition);
+    const tokensToClaim = positionBefore.tokensAllocated.sub(positionBefore.tokensClaimed);
+
+    const tx = await program.methods
+      .claimTokens()
+      .accounts({
         presale: presale, // Provide presale so Anchor can derive token_vault and user_position
         user: user.publicKey,
         userTokenAccount: userTokenAccount,
@@ -480,16 +1474,27 @@ describe("onlypump_presale", () => {
 
     // Create a second presale
     await anyProgram.methods
-      .createPresale(
-        refundMint,
-        authority.publicKey,
+      .createPresale(refundMint, authority.publicKey, {
         publicStartTs,
         publicEndTs,
+        ...noWhitelistWindow,
         publicPriceLamportsPerToken,
-        hardCapLamports
-      )
+        hardCapLamports,
+        softCapLamports: new anchor.BN(0),
+        minContributionLamports: new anchor.BN(0),
+        maxContributionPerWalletLamports: new anchor.BN(0),
+        timeChecksDisabled: true,
+        allocation: defaultAllocation,
+        vesting: noVesting,
+        claimMode: { fixedPrice: {} },
+        lpLock: noLpLock,
+        escrow: noEscrow,
+        creatorBondLamports: new anchor.BN(0),
+        voteConfig: simpleMajority,
+      })
       .accounts({
         admin: owner.publicKey,
+        creator: null,
         mint: refundMint,
       })
       .signers([owner])
@@ -504,7 +1509,7 @@ describe("onlypump_presale", () => {
     const contributionAmount = new anchor.BN(1 * LAMPORTS_PER_SOL);
 
     await anyProgram.methods
-      .contributePublic(contributionAmount)
+      .contributePublic(contributionAmount, false, null)
       .accounts({
         presale: refundPresalePda,
         publicSolVault: refundPublicSolVault,
@@ -528,34 +1533,93 @@ describe("onlypump_presale", () => {
       .signers([owner])
       .rpc();
 
-    // User votes for refund (supportLaunch = false)
+    // User votes for launch in round 1
     await (program as any).methods
-      .castVote(false)
+      .castVote(true)
       .accounts({
         presale: refundPresalePda,
         userPosition: refundUserPosition,
+        voteReceipt: voteReceiptPda(refundPresalePda, 1, user.publicKey),
+        vipPool: null,
+        vipPosition: null,
         voter: user.publicKey,
       })
       .signers([user])
       .rpc();
 
-    // Wait for voting window to end
-    await new Promise((resolve) => setTimeout(resolve, 35_000));
-
+    // Restarting the vote opens round 2 with fresh tallies; the user can vote again
     await (program as any).methods
-      .resolveVote()
+      .startVote(votingEndsTs)
       .accounts({
+        platform: platformConfig,
         presale: refundPresalePda,
+        admin: owner.publicKey,
       })
+      .signers([owner])
       .rpc();
 
-    const refundPresaleAccount: any = await program.account.presale.fetch(refundPresalePda);
-    expect(refundPresaleAccount.refundEnabled).to.be.true;
+    // User votes for launch, then changes to refund (supportLaunch = false) before the end
+    for (const supportLaunch of [true, false]) {
+      await (program as any).methods
+        .castVote(supportLaunch)
+        .accounts({
+          presale: refundPresalePda,
+          userPosition: refundUserPosition,
+          voteReceipt: voteReceiptPda(refundPresalePda, 2, user.publicKey),
+          vipPool: null,
+          vipPosition: null,
+          voter: user.publicKey,
+        })
+        .signers([user])
+        .rpc();
+    }
+    const votingPresale: any = await program.account.presale.fetch(refundPresalePda);
+    expect(votingPresale.voteYesWeight.toNumber()).to.equal(0);
+    expect(votingPresale.voteNoWeight.toString()).to.equal(contributionAmount.toString());
 
-    // Record user balance before refund
-    const balanceBefore = await provider.connection.getBalance(user.publicKey);
+    const firstReceipt: any = await program.account.voteReceipt.fetch(
+      voteReceiptPda(refundPresalePda, 1, user.publicKey)
+    );
+    const secondReceipt: any = await program.account.voteReceipt.fetch(
+      voteReceiptPda(refundPresalePda, 2, user.publicKey)
+    );
+    expect(firstReceipt.supportLaunch).to.be.true;
+    expect(secondReceipt.supportLaunch).to.be.false;
+    expect(secondReceipt.weight.toString()).to.equal(contributionAmount.toString());
 
-    // Claim refund
+    // Wait for voting window to end
+    await new Promise((resolve) => setTimeout(resolve, 35_000));
+
+    // An ended vote can only be resolved, not restarted
+    try {
+      await (program as any).methods
+        .startVote(new anchor.BN(Math.floor(Date.now() / 1000) + 30))
+        .accounts({
+          platform: platformConfig,
+          presale: refundPresalePda,
+          admin: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+      expect.fail("restarting an ended vote should fail");
+    } catch (error: any) {
+      expect(error.toString()).to.include("PresaleNotActive");
+    }
+
+    await (program as any).methods
+      .resolveVote()
+      .accounts({
+        presale: refundPresalePda,
+      })
+      .rpc();
+
+    const refundPresaleAccount: any = await program.account.presale.fetch(refundPresalePda);
+    expect(refundPresaleAccount.refundEnabled).to.be.true;
+
+    // Record user balance before refund
+    const balanceBefore = await provider.connection.getBalance(user.publicKey);
+
+    // Claim refund
     await (program as any).methods
       .claimRefund()
       .accounts({
@@ -574,4 +1638,1477 @@ describe("onlypump_presale", () => {
     const refundPosition = await program.account.userPosition.fetch(refundUserPosition);
     expect(refundPosition.refunded).to.be.true;
   });
+
+  it("Goes straight to refunds when the soft cap is missed", async () => {
+    // Real time window: opened 10s ago, closes in 4s
+    const now = Math.floor(Date.now() / 1000);
+    const failed = await createTestPresale({
+      publicStartTs: new anchor.BN(now - 10),
+      publicEndTs: new anchor.BN(now + 4),
+      softCapLamports: new anchor.BN(5 * LAMPORTS_PER_SOL),
+      timeChecksDisabled: false,
+    });
+    const failedPosition = positionPda(failed.presale, user.publicKey);
+
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(0.1 * LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: failed.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+
+    // Not even the admin can finalize before the end time
+    try {
+      await anyProgram.methods
+        .finalizePresale()
+        .accounts({ presale: failed.presale, caller: owner.publicKey })
+        .signers([owner])
+        .rpc();
+      expect.fail("finalize before public_end_ts should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("PresaleNotEnded");
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 6_000));
+
+    // After the end anyone can finalize
+    await anyProgram.methods
+      .finalizePresale()
+      .accounts({ presale: failed.presale, caller: user.publicKey })
+      .signers([user])
+      .rpc();
+
+    const failedAccount: any = await program.account.presale.fetch(failed.presale);
+    expect(failedAccount.isFinalized).to.be.true;
+    expect(failedAccount.refundEnabled).to.be.true;
+    expect(failedAccount.phase).to.deep.equal({ refundable: {} });
+
+    await anyProgram.methods
+      .claimRefund()
+      .accounts({
+        presale: failed.presale,
+        publicSolVault: failed.publicSolVault,
+        userPosition: failedPosition,
+        user: user.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    const refunded = await program.account.userPosition.fetch(failedPosition);
+    expect(refunded.refunded).to.be.true;
+  });
+
+  it("Falls back to the default outcome when the vote misses quorum", async () => {
+    // 50% quorum, 2/3 approval, refund without quorum
+    const quorum = await createTestPresale({
+      voteConfig: { ...simpleMajority, quorumBps: 5_000, approvalBps: 6_667 },
+    });
+    const backer = Keypair.generate();
+    await provider.connection.requestAirdrop(backer.publicKey, 5 * LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    for (const [wallet, sol] of [
+      [user, 1],
+      [backer, 3],
+    ] as [Keypair, number][]) {
+      await anyProgram.methods
+        .contributePublic(new anchor.BN(sol * LAMPORTS_PER_SOL), false, null)
+        .accounts({ presale: quorum.presale, user: wallet.publicKey, whitelist: null })
+        .signers([wallet])
+        .rpc();
+    }
+
+    // Only the 1 SOL contributor votes (yes): 25% turnout is below quorum
+    await finalizeWithLaunchVote(quorum.presale);
+
+    const presaleAccount: any = await program.account.presale.fetch(quorum.presale);
+    expect(presaleAccount.phase).to.deep.equal({ refundable: {} });
+    expect(presaleAccount.outcome).to.equal(2);
+    expect(presaleAccount.refundEnabled).to.be.true;
+  });
+
+  it("Counts delegated positions in the delegate's vote until the delegator overrides", async () => {
+    const delegated = await createTestPresale({
+      voteConfig: { ...simpleMajority, quorumBps: 5_000 },
+    });
+    const backer = Keypair.generate();
+    await provider.connection.requestAirdrop(backer.publicKey, 5 * LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    for (const [wallet, sol] of [
+      [user, 1],
+      [backer, 3],
+    ] as [Keypair, number][]) {
+      await anyProgram.methods
+        .contributePublic(new anchor.BN(sol * LAMPORTS_PER_SOL), false, null)
+        .accounts({ presale: delegated.presale, user: wallet.publicKey, whitelist: null })
+        .signers([wallet])
+        .rpc();
+    }
+    const backerPosition = positionPda(delegated.presale, backer.publicKey);
+    await anyProgram.methods
+      .delegateVote(user.publicKey)
+      .accounts({ presale: delegated.presale, userPosition: backerPosition, user: backer.publicKey })
+      .signers([backer])
+      .rpc();
+
+    await anyProgram.methods
+      .startVote(new anchor.BN(Math.floor(Date.now() / 1000) + 10))
+      .accounts({ platform: platformConfig, presale: delegated.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    const backerReceipt = voteReceiptPda(delegated.presale, 1, backer.publicKey);
+    await anyProgram.methods
+      .castVote(true)
+      .accounts({
+        presale: delegated.presale,
+        userPosition: positionPda(delegated.presale, user.publicKey),
+        voteReceipt: voteReceiptPda(delegated.presale, 1, user.publicKey),
+        vipPool: null,
+        vipPosition: null,
+        voter: user.publicKey,
+      })
+      .remainingAccounts([
+        { pubkey: backerPosition, isSigner: false, isWritable: false },
+        // No VIP position: the program id stands in for it
+        { pubkey: program.programId, isSigner: false, isWritable: false },
+        { pubkey: backerReceipt, isSigner: false, isWritable: true },
+      ])
+      .signers([user])
+      .rpc();
+
+    let presaleAccount: any = await program.account.presale.fetch(delegated.presale);
+    expect(presaleAccount.voteYesWeight.toNumber()).to.equal(4 * LAMPORTS_PER_SOL);
+    const receipt: any = await program.account.voteReceipt.fetch(backerReceipt);
+    expect(receipt.castBy.toBase58()).to.equal(user.publicKey.toBase58());
+
+    // The backer votes themselves: their 3 SOL move to the refund side
+    await anyProgram.methods
+      .castVote(false)
+      .accounts({
+        presale: delegated.presale,
+        userPosition: backerPosition,
+        voteReceipt: backerReceipt,
+        vipPool: null,
+        vipPosition: null,
+        voter: backer.publicKey,
+      })
+      .signers([backer])
+      .rpc();
+    presaleAccount = await program.account.presale.fetch(delegated.presale);
+    expect(presaleAccount.voteYesWeight.toNumber()).to.equal(LAMPORTS_PER_SOL);
+    expect(presaleAccount.voteNoWeight.toNumber()).to.equal(3 * LAMPORTS_PER_SOL);
+
+    await anyProgram.methods
+      .finalizePresale()
+      .accounts({ presale: delegated.presale, caller: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 12_000));
+    await anyProgram.methods.resolveVote().accounts({ presale: delegated.presale }).rpc();
+    presaleAccount = await program.account.presale.fetch(delegated.presale);
+    expect(presaleAccount.phase).to.deep.equal({ refundable: {} });
+  });
+
+  it("Lets a delegate without a stake vote the delegator's public and VIP positions", async () => {
+    const delegated = await createTestPresale({ voteConfig: { ...simpleMajority, vipWeightBps: 20_000 } });
+    const [vipPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_pool"), delegated.presale.toBuffer()],
+      program.programId
+    );
+    const backer = Keypair.generate();
+    const proxy = Keypair.generate();
+    await provider.connection.requestAirdrop(backer.publicKey, 3 * LAMPORTS_PER_SOL);
+    await provider.connection.requestAirdrop(proxy.publicKey, LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    // The backer puts 1 SOL in the public round and 0.5 SOL in the VIP round
+    await anyProgram.methods
+      .createVipPool(new anchor.BN(500_000), new anchor.BN(2 * LAMPORTS_PER_SOL))
+      .accounts({ presale: delegated.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .whitelistUser(1, new anchor.BN(0))
+      .accounts({ presale: delegated.presale, admin: owner.publicKey, user: backer.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .contributeVip(new anchor.BN(0.5 * LAMPORTS_PER_SOL), null)
+      .accounts({
+        presale: delegated.presale,
+        user: backer.publicKey,
+        whitelist: PublicKey.findProgramAddressSync(
+          [Buffer.from("whitelist"), delegated.presale.toBuffer(), backer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+      })
+      .signers([backer])
+      .rpc();
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: delegated.presale, user: backer.publicKey, whitelist: null })
+      .signers([backer])
+      .rpc();
+    const backerPosition = positionPda(delegated.presale, backer.publicKey);
+    await anyProgram.methods
+      .delegateVote(proxy.publicKey)
+      .accounts({ presale: delegated.presale, userPosition: backerPosition, user: backer.publicKey })
+      .signers([backer])
+      .rpc();
+
+    await anyProgram.methods
+      .startVote(new anchor.BN(Math.floor(Date.now() / 1000) + 30))
+      .accounts({ platform: platformConfig, presale: delegated.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    const backerReceipt = voteReceiptPda(delegated.presale, 1, backer.publicKey);
+    const proxyVote = (delegations: PublicKey[]) =>
+      anyProgram.methods
+        .castVote(true)
+        .accounts({
+          presale: delegated.presale,
+          userPosition: null,
+          voteReceipt: voteReceiptPda(delegated.presale, 1, proxy.publicKey),
+          vipPool: null,
+          vipPosition: null,
+          voter: proxy.publicKey,
+        })
+        .remainingAccounts(
+          delegations.map((pubkey, i) => ({ pubkey, isSigner: false, isWritable: i % 3 === 2 }))
+        )
+        .signers([proxy])
+        .rpc();
+
+    // Without stake or delegations there is nothing to vote with
+    try {
+      await proxyVote([]);
+      expect.fail("a voter without weight should be rejected");
+    } catch (err: any) {
+      expect(err.toString()).to.include("NoVotingWeight");
+    }
+
+    const backerVipPosition = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_position"), vipPool.toBuffer(), backer.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    await proxyVote([backerPosition, backerVipPosition, backerReceipt]);
+
+    // 1 SOL public + 0.5 SOL VIP counted double
+    const presaleAccount: any = await program.account.presale.fetch(delegated.presale);
+    expect(presaleAccount.voteYesWeight.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+    expect(presaleAccount.voteTurnoutLamports.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
+    const receipt: any = await program.account.voteReceipt.fetch(backerReceipt);
+    expect(receipt.castBy.toBase58()).to.equal(proxy.publicKey.toBase58());
+    expect(receipt.weight.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+  });
+
+  it("Lets VIP positions vote with a multiplier and quadratic weighting", async () => {
+    // VIP lamports count double, then every voter's weight is square-rooted
+    const weighted = await createTestPresale({
+      voteConfig: { ...simpleMajority, vipWeightBps: 20_000, quadratic: true },
+    });
+    const [vipPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_pool"), weighted.presale.toBuffer()],
+      program.programId
+    );
+    const vipVoter = Keypair.generate();
+    await provider.connection.requestAirdrop(vipVoter.publicKey, 3 * LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    const [vipPosition] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_position"), vipPool.toBuffer(), vipVoter.publicKey.toBuffer()],
+      program.programId
+    );
+    const [vipWhitelist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("whitelist"), weighted.presale.toBuffer(), vipVoter.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await anyProgram.methods
+      .createVipPool(new anchor.BN(500_000), new anchor.BN(2 * LAMPORTS_PER_SOL))
+      .accounts({ presale: weighted.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .whitelistUser(1, new anchor.BN(0))
+      .accounts({ presale: weighted.presale, admin: owner.publicKey, user: vipVoter.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .contributeVip(new anchor.BN(LAMPORTS_PER_SOL), null)
+      .accounts({ presale: weighted.presale, user: vipVoter.publicKey, whitelist: vipWhitelist })
+      .signers([vipVoter])
+      .rpc();
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: weighted.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+
+    await anyProgram.methods
+      .startVote(new anchor.BN(Math.floor(Date.now() / 1000) + 30))
+      .accounts({ platform: platformConfig, presale: weighted.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    // The VIP-only contributor votes without a public position
+    await anyProgram.methods
+      .castVote(false)
+      .accounts({
+        presale: weighted.presale,
+        userPosition: null,
+        voteReceipt: voteReceiptPda(weighted.presale, 1, vipVoter.publicKey),
+        vipPool,
+        vipPosition,
+        voter: vipVoter.publicKey,
+      })
+      .signers([vipVoter])
+      .rpc();
+    await anyProgram.methods
+      .castVote(true)
+      .accounts({
+        presale: weighted.presale,
+        userPosition: positionPda(weighted.presale, user.publicKey),
+        voteReceipt: voteReceiptPda(weighted.presale, 1, user.publicKey),
+        vipPool: null,
+        vipPosition: null,
+        voter: user.publicKey,
+      })
+      .signers([user])
+      .rpc();
+
+    const presaleAccount: any = await program.account.presale.fetch(weighted.presale);
+    // sqrt(2 * 1 SOL) vs sqrt(1 SOL)
+    expect(presaleAccount.voteNoWeight.toNumber()).to.equal(Math.floor(Math.sqrt(2 * LAMPORTS_PER_SOL)));
+    expect(presaleAccount.voteYesWeight.toNumber()).to.equal(Math.floor(Math.sqrt(LAMPORTS_PER_SOL)));
+    // Turnout is unweighted: both contributions voted
+    expect(presaleAccount.voteTurnoutLamports.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+  });
+
+  it("Leaves VIP positions out of the vote when their weight is zero", async () => {
+    const publicOnly = await createTestPresale({ voteConfig: { ...simpleMajority, vipWeightBps: 0 } });
+    const [vipPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_pool"), publicOnly.presale.toBuffer()],
+      program.programId
+    );
+    const vipPositionOf = (wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("vip_position"), vipPool.toBuffer(), wallet.toBuffer()],
+        program.programId
+      )[0];
+    const vipVoter = Keypair.generate();
+    await provider.connection.requestAirdrop(vipVoter.publicKey, 2 * LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    await anyProgram.methods
+      .createVipPool(new anchor.BN(500_000), new anchor.BN(2 * LAMPORTS_PER_SOL))
+      .accounts({ presale: publicOnly.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    // vipVoter only contributes to the VIP round, user to both
+    for (const wallet of [vipVoter, user]) {
+      await anyProgram.methods
+        .whitelistUser(1, new anchor.BN(0))
+        .accounts({ presale: publicOnly.presale, admin: owner.publicKey, user: wallet.publicKey })
+        .signers([owner])
+        .rpc();
+      await anyProgram.methods
+        .contributeVip(new anchor.BN(0.5 * LAMPORTS_PER_SOL), null)
+        .accounts({
+          presale: publicOnly.presale,
+          user: wallet.publicKey,
+          whitelist: PublicKey.findProgramAddressSync(
+            [Buffer.from("whitelist"), publicOnly.presale.toBuffer(), wallet.publicKey.toBuffer()],
+            program.programId
+          )[0],
+        })
+        .signers([wallet])
+        .rpc();
+    }
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: publicOnly.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+    await anyProgram.methods
+      .startVote(new anchor.BN(Math.floor(Date.now() / 1000) + 30))
+      .accounts({ platform: platformConfig, presale: publicOnly.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    try {
+      await anyProgram.methods
+        .castVote(false)
+        .accounts({
+          presale: publicOnly.presale,
+          userPosition: null,
+          voteReceipt: voteReceiptPda(publicOnly.presale, 1, vipVoter.publicKey),
+          vipPool,
+          vipPosition: vipPositionOf(vipVoter.publicKey),
+          voter: vipVoter.publicKey,
+        })
+        .signers([vipVoter])
+        .rpc();
+      expect.fail("a zero-weight ballot should be rejected");
+    } catch (err: any) {
+      expect(err.toString()).to.include("NoVotingWeight");
+    }
+
+    // Only the public contribution votes and counts toward turnout
+    await anyProgram.methods
+      .castVote(true)
+      .accounts({
+        presale: publicOnly.presale,
+        userPosition: positionPda(publicOnly.presale, user.publicKey),
+        voteReceipt: voteReceiptPda(publicOnly.presale, 1, user.publicKey),
+        vipPool,
+        vipPosition: vipPositionOf(user.publicKey),
+        voter: user.publicKey,
+      })
+      .signers([user])
+      .rpc();
+    const presaleAccount: any = await program.account.presale.fetch(publicOnly.presale);
+    expect(presaleAccount.voteYesWeight.toNumber()).to.equal(LAMPORTS_PER_SOL);
+    expect(presaleAccount.voteNoWeight.toNumber()).to.equal(0);
+    expect(presaleAccount.voteTurnoutLamports.toNumber()).to.equal(LAMPORTS_PER_SOL);
+  });
+
+  it("Runs a VIP round with its own vault, price and refunds", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const vip = await createTestPresale({
+      publicStartTs: new anchor.BN(now - 10),
+      publicEndTs: new anchor.BN(now + 4),
+      softCapLamports: new anchor.BN(5 * LAMPORTS_PER_SOL),
+      timeChecksDisabled: false,
+    });
+    const [vipPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_pool"), vip.presale.toBuffer()],
+      program.programId
+    );
+    const [vipPosition] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_position"), vipPool.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    const [vipWhitelist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("whitelist"), vip.presale.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Half the public price, 1 SOL VIP cap
+    await anyProgram.methods
+      .createVipPool(new anchor.BN(500_000), new anchor.BN(1 * LAMPORTS_PER_SOL))
+      .accounts({ presale: vip.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .whitelistUser(1, new anchor.BN(0))
+      .accounts({ presale: vip.presale, admin: owner.publicKey, user: user.publicKey })
+      .signers([owner])
+      .rpc();
+
+    // VIP round needs a whitelist grant
+    const outsider = Keypair.generate();
+    await provider.connection.requestAirdrop(outsider.publicKey, LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 500));
+    try {
+      await anyProgram.methods
+        .contributeVip(new anchor.BN(0.1 * LAMPORTS_PER_SOL), null)
+        .accounts({ presale: vip.presale, user: outsider.publicKey, whitelist: null })
+        .signers([outsider])
+        .rpc();
+      expect.fail("non-whitelisted wallet should not enter the VIP round");
+    } catch (error: any) {
+      expect(error.toString()).to.include("NotWhitelisted");
+    }
+
+    await anyProgram.methods
+      .contributeVip(new anchor.BN(0.5 * LAMPORTS_PER_SOL), null)
+      .accounts({ presale: vip.presale, user: user.publicKey, whitelist: vipWhitelist })
+      .signers([user])
+      .rpc();
+
+    try {
+      await anyProgram.methods
+        .contributeVip(new anchor.BN(0.6 * LAMPORTS_PER_SOL), null)
+        .accounts({ presale: vip.presale, user: user.publicKey, whitelist: vipWhitelist })
+        .signers([user])
+        .rpc();
+      expect.fail("VIP hard cap should be enforced");
+    } catch (error: any) {
+      expect(error.toString()).to.include("HardCapExceeded");
+    }
+
+    // 0.5 SOL at 0.0005 SOL/token = 1000 tokens
+    const position: any = await anyProgram.account.vipPosition.fetch(vipPosition);
+    expect(position.tokensAllocated.toString()).to.equal(baseUnits(1_000).toString());
+    let presaleAccount: any = await program.account.presale.fetch(vip.presale);
+    expect(presaleAccount.vipRaisedLamports.toString()).to.equal((0.5 * LAMPORTS_PER_SOL).toString());
+    expect(presaleAccount.totalTokensAllocated.toString()).to.equal(baseUnits(1_000).toString());
+
+    // Revenue split: shares are bounded by 100%
+    try {
+      await anyProgram.methods
+        .configureInfluencer(authority.publicKey, 10_001, 0, 0)
+        .accounts({ presale: vip.presale, admin: owner.publicKey })
+        .signers([owner])
+        .rpc();
+      expect.fail("share above 100% should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("InvalidPresaleConfig");
+    }
+    await anyProgram.methods
+      .configureInfluencer(authority.publicKey, 5_000, 1_000, 1_000)
+      .accounts({ presale: vip.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    // Nothing to claim until the migration payout funds the VIP rewards vault
+    try {
+      await anyProgram.methods
+        .claimVipRewards()
+        .accounts({ presale: vip.presale, user: user.publicKey })
+        .signers([user])
+        .rpc();
+      expect.fail("no VIP rewards before migration");
+    } catch (error: any) {
+      expect(error.toString()).to.include("NothingToClaim");
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 6_000));
+    await anyProgram.methods
+      .finalizePresale()
+      .accounts({ presale: vip.presale, caller: user.publicKey })
+      .signers([user])
+      .rpc();
+
+    const balanceBefore = await provider.connection.getBalance(user.publicKey);
+    await anyProgram.methods
+      .claimVipRefund()
+      .accounts({ presale: vip.presale, user: user.publicKey })
+      .signers([user])
+      .rpc();
+    const balanceAfter = await provider.connection.getBalance(user.publicKey);
+    expect(balanceAfter - balanceBefore).to.be.greaterThan(0.49 * LAMPORTS_PER_SOL);
+
+    presaleAccount = await program.account.presale.fetch(vip.presale);
+    expect(presaleAccount.totalTokensAllocated.toNumber()).to.equal(0);
+  });
+
+  it("Checks the mint's decimals and supply against the allocation in initialize_vaults", async () => {
+    const initializeVaults = (presalePda: PublicKey, mint: PublicKey) =>
+      anyProgram.methods
+        .initializeVaults()
+        .accounts({ presale: presalePda, admin: owner.publicKey, mint })
+        .signers([owner])
+        .rpc();
+
+    // Allocation declared in 6 decimals, mint has 9
+    const nineDecimals = await createMint(provider.connection, authority, authority.publicKey, null, 9);
+    const wrongDecimals = await createTestPresale({}, nineDecimals);
+    try {
+      await initializeVaults(wrongDecimals.presale, nineDecimals);
+      expect.fail("a mint with other decimals than the allocation should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("MintDecimalsMismatch");
+    }
+
+    // One base unit short of public + LP + ecosystem
+    const underSupplied = await createTestPresale();
+    const authorityAta = await getAssociatedTokenAddress(underSupplied.mint, authority.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          authorityAta,
+          authority.publicKey,
+          underSupplied.mint
+        )
+      ),
+      [authority]
+    );
+    const mintToAuthority = (amount: anchor.BN) =>
+      mintTo(provider.connection, authority, underSupplied.mint, authorityAta, authority, BigInt(amount.toString()));
+    await mintToAuthority(baseUnits(TOTAL_PRESALE_TOKENS).subn(1));
+    try {
+      await initializeVaults(underSupplied.presale, underSupplied.mint);
+      expect.fail("a mint supply below the allocation should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("InsufficientMintSupply");
+    }
+
+    // With the full supply only the missing launch vote is left
+    await mintToAuthority(new anchor.BN(1));
+    try {
+      await initializeVaults(underSupplied.presale, underSupplied.mint);
+      expect.fail("initialize_vaults before the launch vote should be rejected");
+    } catch (error: any) {
+      expect(error.toString()).to.include("PresaleNotFinalized");
+    }
+  });
+
+  it("Rejects sealing a FixedPrice presale below the declared allocation", async () => {
+    const underfunded = await createTestPresale();
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(0.1 * LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: underfunded.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+    await finalizeWithLaunchVote(underfunded.presale);
+
+    const authorityAta = await getAssociatedTokenAddress(underfunded.mint, authority.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          authorityAta,
+          authority.publicKey,
+          underfunded.mint
+        )
+      ),
+      [authority]
+    );
+    await mintTo(
+      provider.connection,
+      authority,
+      underfunded.mint,
+      authorityAta,
+      authority,
+      BigInt(baseUnits(TOTAL_PRESALE_TOKENS).toString())
+    );
+    await anyProgram.methods
+      .initializeVaults()
+      .accounts({ presale: underfunded.presale, admin: owner.publicKey, mint: underfunded.mint })
+      .signers([owner])
+      .rpc();
+    const fund = (amount: anchor.BN) =>
+      anyProgram.methods
+        .fundPresaleTokens(amount)
+        .accounts({ presale: underfunded.presale, fromTokenAccount: authorityAta, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+    // One base unit short of the declared public + LP + ecosystem total
+    await fund(baseUnits(TOTAL_PRESALE_TOKENS).subn(1));
+
+    const seal = (publicTokens: anchor.BN, lpTokens: anchor.BN) =>
+      anyProgram.methods
+        .sealFunding(publicTokens, lpTokens, baseUnits(VAULT_ALLOCATION))
+        .accounts({ presale: underfunded.presale, admin: owner.publicKey })
+        .signers([owner])
+        .rpc();
+    // Matches the deposits, but not the declared split
+    for (const [publicTokens, lpTokens] of [
+      [baseUnits(PRESALE_ALLOCATION).subn(1), baseUnits(LP_ALLOCATION)],
+      [baseUnits(PRESALE_ALLOCATION), baseUnits(LP_ALLOCATION).subn(1)],
+    ]) {
+      try {
+        await seal(publicTokens, lpTokens);
+        expect.fail("funding below the declared total should be rejected");
+      } catch (error: any) {
+        expect(error.toString()).to.include("FundingAmountMismatch");
+      }
+    }
+
+    await fund(new anchor.BN(1));
+    await seal(baseUnits(PRESALE_ALLOCATION), baseUnits(LP_ALLOCATION));
+    const presaleAccount: any = await program.account.presale.fetch(underfunded.presale);
+    expect(presaleAccount.fundingSealed).to.be.true;
+  });
+
+  it("Splits the funded public tokens pro-rata in ProRata claim mode", async () => {
+    const proRata = await createTestPresale({
+      claimMode: { proRata: {} },
+      creatorBondLamports: new anchor.BN(LAMPORTS_PER_SOL / 2),
+    });
+    const backer = Keypair.generate();
+    await provider.connection.requestAirdrop(backer.publicKey, 5 * LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    // 1 SOL + 3 SOL raised
+    for (const [wallet, sol] of [
+      [user, 1],
+      [backer, 3],
+    ] as [Keypair, number][]) {
+      await anyProgram.methods
+        .contributePublic(new anchor.BN(sol * LAMPORTS_PER_SOL), false, null)
+        .accounts({ presale: proRata.presale, user: wallet.publicKey, whitelist: null })
+        .signers([wallet])
+        .rpc();
+    }
+
+    await finalizeWithLaunchVote(proRata.presale);
+    await anyProgram.methods
+      .initializeVaults()
+      .accounts({ presale: proRata.presale, admin: owner.publicKey, mint: proRata.mint })
+      .signers([owner])
+      .rpc();
+
+    // Launch bought fewer tokens than the fixed price would need: LP + ecosystem + 100M public
+    const authorityAta = await getAssociatedTokenAddress(proRata.mint, authority.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          authorityAta,
+          authority.publicKey,
+          proRata.mint
+        )
+      ),
+      [authority]
+    );
+    const funded = baseUnits(LP_ALLOCATION + VAULT_ALLOCATION + 100_000_000);
+    await mintTo(
+      provider.connection,
+      authority,
+      proRata.mint,
+      authorityAta,
+      authority,
+      BigInt(funded.toString())
+    );
+    await anyProgram.methods
+      .fundPresaleTokens(funded)
+      .accounts({ presale: proRata.presale, fromTokenAccount: authorityAta, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+    await anyProgram.methods
+      .sealFunding(baseUnits(100_000_000), baseUnits(LP_ALLOCATION), baseUnits(VAULT_ALLOCATION))
+      .accounts({ presale: proRata.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    const proRataPool = mockAmmPool(proRata.presale, proRata.mint);
+    await anyProgram.methods
+      .migrateAndCreateLp({
+        amm: { pumpSwap: {} },
+        lpSolAmount: new anchor.BN(LAMPORTS_PER_SOL),
+        poolCreationLamports: POOL_CREATION_LAMPORTS,
+      })
+      .accounts({
+        presale: proRata.presale,
+        mint: proRata.mint,
+        quoteMint: NATIVE_MINT,
+        ammProgram: MOCK_AMM_PROGRAM_ID,
+        treasury: treasury.publicKey,
+        influencerConfig: null,
+        creator: null,
+        vipPool: null,
+        vipRewardsVault: null,
+        admin: owner.publicKey,
+      })
+      .remainingAccounts(proRataPool.remainingAccounts)
+      .signers([owner])
+      .rpc();
+
+    // No LP lock: the LP tokens go straight to the presale authority
+    const authorityLpAccount = getAssociatedTokenAddressSync(proRataPool.lpMint, authority.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          authorityLpAccount,
+          authority.publicKey,
+          proRataPool.lpMint
+        )
+      ),
+      [authority]
+    );
+    await anyProgram.methods
+      .unlockLp()
+      .accounts({
+        presale: proRata.presale,
+        lpVault: proRataPool.lpTokenAccount,
+        lpMint: proRataPool.lpMint,
+        recipientTokenAccount: authorityLpAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const unlockedLp = await getAccount(provider.connection, authorityLpAccount);
+    expect(Number(unlockedLp.amount)).to.be.greaterThan(0);
+
+    const presaleAccount: any = await program.account.presale.fetch(proRata.presale);
+    expect(presaleAccount.fundedPublicTokens.toString()).to.equal(baseUnits(100_000_000).toString());
+
+    // 1 of 4 SOL raised -> a quarter of the funded public tokens, not the 1000 tokens 1 SOL bought at the fixed price
+    const userAta = await getAssociatedTokenAddress(proRata.mint, user.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(user.publicKey, userAta, user.publicKey, proRata.mint)
+      ),
+      [user]
+    );
+    await program.methods
+      .claimTokens()
+      .accounts({ presale: proRata.presale, user: user.publicKey, userTokenAccount: userAta })
+      .signers([user])
+      .rpc();
+    const claimed = await getAccount(provider.connection, userAta);
+    expect(claimed.amount.toString()).to.equal(baseUnits(25_000_000).toString());
+
+    // Launched with sealed funding: the creator bond goes back to the authority
+    const authorityBefore = await provider.connection.getBalance(authority.publicKey);
+    await anyProgram.methods
+      .returnCreatorBond()
+      .accounts({ presale: proRata.presale, authority: authority.publicKey })
+      .rpc();
+    expect((await provider.connection.getBalance(authority.publicKey)) - authorityBefore).to.equal(
+      LAMPORTS_PER_SOL / 2
+    );
+  });
+
+  it("Launches on pump.fun with the raised SOL and buys into the token vault", async () => {
+    await anyProgram.methods
+      .updatePlatformConfig({ operator: null, treasury: null, feeBps: null, pumpfunProgram: MOCK_PUMPFUN_PROGRAM_ID })
+      .accounts({ owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    // The mint is only reserved here; pump.fun creates it at launch
+    const mintKeypair = Keypair.generate();
+    const launch = await createTestPresale({ claimMode: { proRata: {} } }, mintKeypair.publicKey);
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(2 * LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: launch.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+
+    const tokenAmount = baseUnits(1_000_000);
+    const launchAccounts = {
+      presale: launch.presale,
+      mint: mintKeypair.publicKey,
+      pumpfunProgram: MOCK_PUMPFUN_PROGRAM_ID,
+      pumpfunMintAuthority: PublicKey.findProgramAddressSync(
+        [Buffer.from("mint-authority")],
+        MOCK_PUMPFUN_PROGRAM_ID
+      )[0],
+      bondingCurve: PublicKey.findProgramAddressSync(
+        [Buffer.from("bonding-curve"), mintKeypair.publicKey.toBuffer()],
+        MOCK_PUMPFUN_PROGRAM_ID
+      )[0],
+      associatedBondingCurve: PublicKey.default,
+      // Ignored by the mock
+      pumpfunGlobal: Keypair.generate().publicKey,
+      pumpfunFeeRecipient: Keypair.generate().publicKey,
+      mplTokenMetadata: Keypair.generate().publicKey,
+      metadata: Keypair.generate().publicKey,
+      creatorVault: Keypair.generate().publicKey,
+      pumpfunEventAuthority: Keypair.generate().publicKey,
+      admin: owner.publicKey,
+    };
+    launchAccounts.associatedBondingCurve = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      launchAccounts.bondingCurve,
+      true
+    );
+    const launchOnPumpfun = () =>
+      anyProgram.methods
+        .launchOnPumpfun({
+          name: "Launch",
+          symbol: "LNCH",
+          uri: "https://example.com/launch.json",
+          tokenAmount,
+          maxSolCost: new anchor.BN(LAMPORTS_PER_SOL),
+        })
+        .accounts(launchAccounts)
+        .signers([owner, mintKeypair])
+        .rpc();
+
+    // No launch before the community voted for it
+    try {
+      await launchOnPumpfun();
+      expect.fail("launch before a Launch outcome should fail");
+    } catch (err: any) {
+      expect(err.toString()).to.include("PresaleNotFinalized");
+    }
+
+    await finalizeWithLaunchVote(launch.presale);
+
+    const authorityBalanceBefore = await provider.connection.getBalance(authority.publicKey);
+    const vaultBefore = await provider.connection.getBalance(launch.publicSolVault);
+    const feeVaultBefore = await provider.connection.getBalance(feeVault);
+    await launchOnPumpfun();
+
+    const [tokenVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_vault"), launch.presale.toBuffer()],
+      program.programId
+    );
+    const vault = await getAccount(provider.connection, tokenVaultPda);
+    expect(vault.amount.toString()).to.equal(tokenAmount.toString());
+    expect(vault.owner.toBase58()).to.equal(tokenVaultPda.toBase58());
+
+    const presaleAccount: any = await program.account.presale.fetch(launch.presale);
+    expect(presaleAccount.fundedTokens.toString()).to.equal(tokenAmount.toString());
+    expect(presaleAccount.phase).to.have.property("launched");
+
+    // Unspent SOL went back to the vault; the creator never received any
+    const [launchBuyer] = PublicKey.findProgramAddressSync(
+      [Buffer.from("launch_buyer"), launch.presale.toBuffer()],
+      program.programId
+    );
+    expect(await provider.connection.getBalance(launchBuyer)).to.equal(0);
+    expect(await provider.connection.getBalance(launch.publicSolVault)).to.be.greaterThan(LAMPORTS_PER_SOL);
+    expect(await provider.connection.getBalance(authority.publicKey)).to.equal(authorityBalanceBefore);
+
+    // The 1% fee covers only the SOL pump.fun took; the rest is charged when it leaves the vault
+    const feeCharged = (await provider.connection.getBalance(feeVault)) - feeVaultBefore;
+    const solSpent = vaultBefore - (await provider.connection.getBalance(launch.publicSolVault)) - feeCharged;
+    expect(solSpent).to.be.greaterThan(0);
+    expect(feeCharged).to.equal(Math.floor((solSpent * 100) / 10_000));
+  });
+
+  it("Releases creator SOL in escrow tranches and refunds the remainder", async () => {
+    const escrowed = await createTestPresale({
+      escrow: { launchTrancheBps: 3_000, releaseDelaySeconds: new anchor.BN(2) },
+      creatorBondLamports: new anchor.BN(LAMPORTS_PER_SOL / 2),
+    });
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: escrowed.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+    await finalizeWithLaunchVote(escrowed.presale);
+
+    // First tranche: 30% of the raise, minus the 1% platform fee
+    const withdraw = () =>
+      anyProgram.methods
+        .withdrawForLaunch()
+        .accounts({ presale: escrowed.presale, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+    const authorityBefore = await provider.connection.getBalance(authority.publicKey);
+    await withdraw();
+    const tranche = 0.3 * LAMPORTS_PER_SOL;
+    expect((await provider.connection.getBalance(authority.publicKey)) - authorityBefore).to.equal(
+      tranche - (tranche * 100) / 10_000
+    );
+
+    try {
+      await withdraw();
+      expect.fail("the launch tranche can only be withdrawn once");
+    } catch (err: any) {
+      expect(err.toString()).to.include("LaunchFundsAlreadyWithdrawn");
+    }
+
+    // The rest needs a funded and sealed token vault
+    try {
+      await anyProgram.methods
+        .releaseEscrow()
+        .accounts({ presale: escrowed.presale, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+      expect.fail("release before sealing funding should fail");
+    } catch (err: any) {
+      expect(err.toString()).to.include("FundingNotSealed");
+    }
+
+    // Creator never funds the vault: after the deadline contributors get the remaining 70%
+    // plus the slashed creator bond (all of it here, the user is the only contributor)
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    await anyProgram.methods.enableEscrowRefunds().accounts({ presale: escrowed.presale }).rpc();
+
+    try {
+      await anyProgram.methods
+        .returnCreatorBond()
+        .accounts({ presale: escrowed.presale, authority: authority.publicKey })
+        .rpc();
+      expect.fail("a slashed bond cannot be returned");
+    } catch (err: any) {
+      expect(err.toString()).to.include("CreatorBondNotReturnable");
+    }
+
+    const userBefore = await provider.connection.getBalance(user.publicKey);
+    await program.methods
+      .claimRefund()
+      .accounts({
+        presale: escrowed.presale,
+        publicSolVault: escrowed.publicSolVault,
+        userPosition: positionPda(escrowed.presale, user.publicKey),
+        user: user.publicKey,
+      })
+      .signers([user])
+      .rpc();
+    expect((await provider.connection.getBalance(user.publicKey)) - userBefore).to.equal(
+      0.7 * LAMPORTS_PER_SOL + LAMPORTS_PER_SOL / 2
+    );
+  });
+
+  it("Releases the escrow once funding is sealed and only then allows migration", async () => {
+    const escrowed = await createTestPresale({
+      escrow: { launchTrancheBps: 3_000, releaseDelaySeconds: new anchor.BN(3600) },
+    });
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: escrowed.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+    await finalizeWithLaunchVote(escrowed.presale);
+    await anyProgram.methods
+      .withdrawForLaunch()
+      .accounts({ presale: escrowed.presale, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+    await fundAndSealVaults(escrowed.presale, escrowed.mint);
+
+    // The escrowed 70% still belongs to release_escrow, not to the LP
+    const migrate = () =>
+      anyProgram.methods
+        .migrateAndCreateLp({
+          amm: { pumpSwap: {} },
+          lpSolAmount: new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+          poolCreationLamports: POOL_CREATION_LAMPORTS,
+        })
+        .accounts({
+          presale: escrowed.presale,
+          mint: escrowed.mint,
+          quoteMint: NATIVE_MINT,
+          ammProgram: MOCK_AMM_PROGRAM_ID,
+          treasury: treasury.publicKey,
+          influencerConfig: null,
+          creator: null,
+          vipPool: null,
+          vipRewardsVault: null,
+          admin: owner.publicKey,
+        })
+        .remainingAccounts(mockAmmPool(escrowed.presale, escrowed.mint).remainingAccounts)
+        .signers([owner])
+        .rpc();
+    try {
+      await migrate();
+      expect.fail("migration with SOL still in escrow should fail");
+    } catch (err: any) {
+      expect(err.toString()).to.include("EscrowPending");
+    }
+
+    // Sealed funding releases the rest of the vault to the creator, minus the 1% platform fee
+    const vaultBefore = await provider.connection.getBalance(escrowed.publicSolVault);
+    expect(vaultBefore).to.be.greaterThan(0.7 * LAMPORTS_PER_SOL);
+    const authorityBefore = await provider.connection.getBalance(authority.publicKey);
+    await anyProgram.methods
+      .releaseEscrow()
+      .accounts({ presale: escrowed.presale, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+    expect((await provider.connection.getBalance(authority.publicKey)) - authorityBefore).to.equal(
+      vaultBefore - Math.floor((vaultBefore * 100) / 10_000)
+    );
+    expect(await provider.connection.getBalance(escrowed.publicSolVault)).to.equal(0);
+    const presaleAccount: any = await program.account.presale.fetch(escrowed.presale);
+    expect(presaleAccount.escrowReleasedBps).to.equal(10_000);
+
+    // Released: migration is no longer held back by the escrow (the vault is empty now)
+    try {
+      await migrate();
+      expect.fail("migration without SOL in the vault should fail");
+    } catch (err: any) {
+      expect(err.toString()).to.include("InsufficientFunds");
+    }
+  });
+
+  it("Splits the migration payout between creator, treasury and VIP holders", async () => {
+    const split = await createTestPresale();
+    const [vipPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_pool"), split.presale.toBuffer()],
+      program.programId
+    );
+    const [vipRewardsVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_rewards_vault"), split.presale.toBuffer()],
+      program.programId
+    );
+    const [influencerConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("influencer"), split.presale.toBuffer()],
+      program.programId
+    );
+    await anyProgram.methods
+      .createVipPool(new anchor.BN(500_000), new anchor.BN(2 * LAMPORTS_PER_SOL))
+      .accounts({ presale: split.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .whitelistUser(1, new anchor.BN(0))
+      .accounts({ presale: split.presale, admin: owner.publicKey, user: user.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .contributeVip(new anchor.BN(LAMPORTS_PER_SOL), null)
+      .accounts({
+        presale: split.presale,
+        user: user.publicKey,
+        whitelist: PublicKey.findProgramAddressSync(
+          [Buffer.from("whitelist"), split.presale.toBuffer(), user.publicKey.toBuffer()],
+          program.programId
+        )[0],
+      })
+      .signers([user])
+      .rpc();
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: split.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+
+    // Creator gets 50% of the payout, minus 10% for VIP holders; 10% of the platform half goes to VIP too
+    const creator = Keypair.generate().publicKey;
+    await anyProgram.methods
+      .configureInfluencer(creator, 5_000, 1_000, 1_000)
+      .accounts({ presale: split.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await finalizeWithLaunchVote(split.presale);
+    await fundAndSealVaults(split.presale, split.mint);
+
+    const migrate = (splitAccounts: Record<string, PublicKey | null>) =>
+      anyProgram.methods
+        .migrateAndCreateLp({
+          amm: { pumpSwap: {} },
+          lpSolAmount: new anchor.BN(0.5 * LAMPORTS_PER_SOL),
+          poolCreationLamports: POOL_CREATION_LAMPORTS,
+        })
+        .accounts({
+          presale: split.presale,
+          mint: split.mint,
+          quoteMint: NATIVE_MINT,
+          ammProgram: MOCK_AMM_PROGRAM_ID,
+          treasury: treasury.publicKey,
+          ...splitAccounts,
+          admin: owner.publicKey,
+        })
+        .remainingAccounts(mockAmmPool(split.presale, split.mint).remainingAccounts)
+        .signers([owner])
+        .rpc();
+
+    // Leaving out the split config or the VIP accounts would send everything to the treasury
+    for (const splitAccounts of [
+      { influencerConfig: null, creator: null, vipPool, vipRewardsVault },
+      { influencerConfig, creator, vipPool: null, vipRewardsVault: null },
+    ]) {
+      try {
+        await migrate(splitAccounts);
+        expect.fail("migration must apply the revenue split");
+      } catch (err: any) {
+        expect(err.toString()).to.include("InvalidPresaleConfig");
+      }
+    }
+
+    const treasuryBefore = await provider.connection.getBalance(treasury.publicKey);
+    const vipVaultBefore = await provider.connection.getBalance(vipRewardsVault);
+    await migrate({ influencerConfig, creator, vipPool, vipRewardsVault });
+    const creatorLamports = await provider.connection.getBalance(creator);
+    const platformLamports = (await provider.connection.getBalance(treasury.publicKey)) - treasuryBefore;
+    const vipLamports = (await provider.connection.getBalance(vipRewardsVault)) - vipVaultBefore;
+
+    const payout = creatorLamports + platformLamports + vipLamports;
+    const creatorGross = Math.floor(payout / 2);
+    const vipFromCreator = Math.floor(creatorGross / 10);
+    const vipFromPlatform = Math.floor((payout - creatorGross) / 10);
+    expect(creatorLamports).to.equal(creatorGross - vipFromCreator);
+    expect(vipLamports).to.equal(vipFromCreator + vipFromPlatform);
+    expect(platformLamports).to.equal(payout - creatorGross - vipFromPlatform);
+    expect(await provider.connection.getBalance(split.publicSolVault)).to.equal(0);
+    const pool: any = await anyProgram.account.vipPool.fetch(vipPool);
+    expect(pool.totalRewardsLamports.toNumber()).to.equal(vipLamports);
+
+    // The only VIP contributor takes all of the VIP rewards
+    const userBefore = await provider.connection.getBalance(user.publicKey);
+    await anyProgram.methods
+      .claimVipRewards()
+      .accounts({ presale: split.presale, user: user.publicKey })
+      .signers([user])
+      .rpc();
+    expect((await provider.connection.getBalance(user.publicKey)) - userBefore).to.equal(vipLamports);
+    try {
+      await anyProgram.methods
+        .claimVipRewards()
+        .accounts({ presale: split.presale, user: user.publicKey })
+        .signers([user])
+        .rpc();
+      expect.fail("rewards can only be claimed once");
+    } catch (err: any) {
+      expect(err.toString()).to.include("NothingToClaim");
+    }
+  });
+
+  it("Migrates into a Raydium CPMM pool", async () => {
+    await anyProgram.methods
+      .updatePlatformConfig({ operator: null, treasury: null, feeBps: null, raydiumCpmmProgram: MOCK_AMM_PROGRAM_ID })
+      .accounts({ owner: owner.publicKey })
+      .signers([owner])
+      .rpc();
+
+    const cpmm = await createTestPresale();
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: cpmm.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+    await finalizeWithLaunchVote(cpmm.presale);
+    await fundAndSealVaults(cpmm.presale, cpmm.mint);
+
+    const lpSolAmount = new anchor.BN(0.5 * LAMPORTS_PER_SOL);
+    const pool = mockCpmmPool(cpmm.presale, cpmm.mint);
+    await anyProgram.methods
+      .migrateAndCreateLp({
+        amm: { raydiumCpmm: {} },
+        lpSolAmount,
+        poolCreationLamports: POOL_CREATION_LAMPORTS,
+      })
+      .accounts({
+        presale: cpmm.presale,
+        mint: cpmm.mint,
+        quoteMint: NATIVE_MINT,
+        ammProgram: MOCK_AMM_PROGRAM_ID,
+        treasury: treasury.publicKey,
+        influencerConfig: null,
+        creator: null,
+        vipPool: null,
+        vipRewardsVault: null,
+        admin: owner.publicKey,
+      })
+      .remainingAccounts(pool.remainingAccounts)
+      .signers([owner])
+      .rpc();
+
+    // Both sides landed in the pool vaults and the LP tokens are locked with lp_authority
+    expect((await getAccount(provider.connection, pool.baseVault)).amount.toString()).to.equal(
+      baseUnits(LP_ALLOCATION).toString()
+    );
+    expect((await getAccount(provider.connection, pool.quoteVault)).amount.toString()).to.equal(
+      lpSolAmount.toString()
+    );
+    const lpTokens = await getAccount(provider.connection, pool.lpTokenAccount);
+    expect(lpTokens.owner.toBase58()).to.equal(pool.lpAuthority.toBase58());
+    expect(Number(lpTokens.amount)).to.be.greaterThan(0);
+    const presaleAccount: any = await program.account.presale.fetch(cpmm.presale);
+    expect(presaleAccount.isMigrated).to.be.true;
+    expect(presaleAccount.lpMint.toBase58()).to.equal(pool.lpMint.toBase58());
+    expect(presaleAccount.lpVault.toBase58()).to.equal(pool.lpTokenAccount.toBase58());
+    expect(presaleAccount.lpLockedAmount.toString()).to.equal(lpTokens.amount.toString());
+    expect(await provider.connection.getBalance(pool.lpAuthority)).to.equal(0);
+  });
+
+  it("Slashes the creator bond to refunding contributors when the launch deadline passes", async () => {
+    // Time checks disabled: no 24h grace, the launch deadline is max(now, tge_ts = public_end_ts)
+    const bond = LAMPORTS_PER_SOL + 1;
+    const slashed = await createTestPresale({
+      publicEndTs: new anchor.BN(Math.floor(Date.now() / 1000) + 2),
+      creatorBondLamports: new anchor.BN(bond),
+    });
+    const [vipPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vip_pool"), slashed.presale.toBuffer()],
+      program.programId
+    );
+    const [creatorBond] = PublicKey.findProgramAddressSync(
+      [Buffer.from("creator_bond"), slashed.presale.toBuffer()],
+      program.programId
+    );
+    const backer = Keypair.generate();
+    await provider.connection.requestAirdrop(backer.publicKey, 3 * LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    // user: 1 SOL public, backer: 2 SOL VIP
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: slashed.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+    await anyProgram.methods
+      .createVipPool(new anchor.BN(500_000), new anchor.BN(2 * LAMPORTS_PER_SOL))
+      .accounts({ presale: slashed.presale, admin: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .whitelistUser(1, new anchor.BN(0))
+      .accounts({ presale: slashed.presale, admin: owner.publicKey, user: backer.publicKey })
+      .signers([owner])
+      .rpc();
+    await anyProgram.methods
+      .contributeVip(new anchor.BN(2 * LAMPORTS_PER_SOL), null)
+      .accounts({
+        presale: slashed.presale,
+        user: backer.publicKey,
+        whitelist: PublicKey.findProgramAddressSync(
+          [Buffer.from("whitelist"), slashed.presale.toBuffer(), backer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+      })
+      .signers([backer])
+      .rpc();
+
+    await finalizeWithLaunchVote(slashed.presale);
+    try {
+      await anyProgram.methods
+        .returnCreatorBond()
+        .accounts({ presale: slashed.presale, authority: authority.publicKey })
+        .rpc();
+      expect.fail("the bond stays locked until the presale launches or refunds");
+    } catch (err: any) {
+      expect(err.toString()).to.include("CreatorBondNotReturnable");
+    }
+
+    // The creator never launches, and cannot take the raise once the deadline has passed
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    try {
+      await anyProgram.methods
+        .withdrawForLaunch()
+        .accounts({ presale: slashed.presale, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+      expect.fail("withdrawing after the launch deadline should fail");
+    } catch (err: any) {
+      expect(err.toString()).to.include("LaunchDeadlinePassed");
+    }
+    await anyProgram.methods.enableRefundsIfDeadlinePassed().accounts({ presale: slashed.presale }).rpc();
+    const presaleAccount: any = await program.account.presale.fetch(slashed.presale);
+    expect(presaleAccount.phase).to.deep.equal({ refundable: {} });
+    expect(presaleAccount.creatorBondSlashed).to.be.true;
+
+    // Each refund adds bond * contribution / 3 SOL raised, rounded down
+    const bondBefore = await provider.connection.getBalance(creatorBond);
+    const userShare = Math.floor(bond / 3);
+    const backerShare = Math.floor((bond * 2) / 3);
+    const userBefore = await provider.connection.getBalance(user.publicKey);
+    await program.methods
+      .claimRefund()
+      .accounts({
+        presale: slashed.presale,
+        publicSolVault: slashed.publicSolVault,
+        userPosition: positionPda(slashed.presale, user.publicKey),
+        user: user.publicKey,
+      })
+      .signers([user])
+      .rpc();
+    expect((await provider.connection.getBalance(user.publicKey)) - userBefore).to.equal(
+      LAMPORTS_PER_SOL + userShare
+    );
+
+    const backerBefore = await provider.connection.getBalance(backer.publicKey);
+    await anyProgram.methods
+      .claimVipRefund()
+      .accounts({ presale: slashed.presale, user: backer.publicKey })
+      .signers([backer])
+      .rpc();
+    expect((await provider.connection.getBalance(backer.publicKey)) - backerBefore).to.equal(
+      2 * LAMPORTS_PER_SOL + backerShare
+    );
+
+    // The rounding remainder (1 lamport here) stays in the bond account
+    const bondAfter = await provider.connection.getBalance(creatorBond);
+    expect(bondBefore - bondAfter).to.equal(userShare + backerShare);
+    expect(bond - userShare - backerShare).to.equal(1);
+    expect(bondAfter - (await provider.connection.getMinimumBalanceForRentExemption(8))).to.equal(1);
+  });
+
+  it("Pays out the slashed bond when the creator withdrew the raise and missed the deadline", async () => {
+    // Launch deadline = tge_ts = public_end_ts: after the vote resolves, before the test sleeps past it
+    const deadline = Math.floor(Date.now() / 1000) + 20;
+    const bond = LAMPORTS_PER_SOL / 2;
+    const slashed = await createTestPresale({
+      publicEndTs: new anchor.BN(deadline),
+      creatorBondLamports: new anchor.BN(bond),
+    });
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: slashed.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+    await finalizeWithLaunchVote(slashed.presale);
+
+    // No escrow: the creator takes the whole raise, then never launches
+    await anyProgram.methods
+      .withdrawForLaunch()
+      .accounts({ presale: slashed.presale, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, Math.max(0, (deadline + 2) * 1000 - Date.now())));
+    await anyProgram.methods.enableRefundsIfDeadlinePassed().accounts({ presale: slashed.presale }).rpc();
+
+    // Nothing is left in the vault to refund, but the whole bond goes to the only contributor
+    const userBefore = await provider.connection.getBalance(user.publicKey);
+    await program.methods
+      .claimRefund()
+      .accounts({
+        presale: slashed.presale,
+        publicSolVault: slashed.publicSolVault,
+        userPosition: positionPda(slashed.presale, user.publicKey),
+        user: user.publicKey,
+      })
+      .signers([user])
+      .rpc();
+    expect((await provider.connection.getBalance(user.publicKey)) - userBefore).to.equal(bond);
+    const position: any = await program.account.userPosition.fetch(positionPda(slashed.presale, user.publicKey));
+    expect(position.refunded).to.be.true;
+  });
+
+  it("Unlocks the LP tokens to the lock recipient once the lock expires", async () => {
+    const locked = await createTestPresale({
+      lpLock: { lockSeconds: new anchor.BN(5), recipient: treasury.publicKey },
+    });
+    await anyProgram.methods
+      .contributePublic(new anchor.BN(LAMPORTS_PER_SOL), false, null)
+      .accounts({ presale: locked.presale, user: user.publicKey, whitelist: null })
+      .signers([user])
+      .rpc();
+    await finalizeWithLaunchVote(locked.presale);
+    await fundAndSealVaults(locked.presale, locked.mint);
+    const pool = mockAmmPool(locked.presale, locked.mint);
+    await anyProgram.methods
+      .migrateAndCreateLp({
+        amm: { pumpSwap: {} },
+        lpSolAmount: new anchor.BN(0.5 * LAMPORTS_PER_SOL),
+        poolCreationLamports: POOL_CREATION_LAMPORTS,
+      })
+      .accounts({
+        presale: locked.presale,
+        mint: locked.mint,
+        quoteMint: NATIVE_MINT,
+        ammProgram: MOCK_AMM_PROGRAM_ID,
+        treasury: treasury.publicKey,
+        influencerConfig: null,
+        creator: null,
+        vipPool: null,
+        vipRewardsVault: null,
+        admin: owner.publicKey,
+      })
+      .remainingAccounts(pool.remainingAccounts)
+      .signers([owner])
+      .rpc();
+
+    const lpAccountOf = async (wallet: PublicKey) => {
+      const account = getAssociatedTokenAddressSync(pool.lpMint, wallet);
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          createAssociatedTokenAccountInstruction(authority.publicKey, account, wallet, pool.lpMint)
+        ),
+        [authority]
+      );
+      return account;
+    };
+    const unlock = (recipientTokenAccount: PublicKey) =>
+      anyProgram.methods
+        .unlockLp()
+        .accounts({
+          presale: locked.presale,
+          lpVault: pool.lpTokenAccount,
+          lpMint: pool.lpMint,
+          recipientTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    // Only an LP account owned by lp_lock.recipient can receive the unlocked LP
+    const presaleAccount: any = await program.account.presale.fetch(locked.presale);
+    const lockedAmount = presaleAccount.lpLockedAmount.toString();
+    await new Promise((resolve) =>
+      setTimeout(resolve, Math.max(0, (presaleAccount.lpUnlockTs.toNumber() + 2) * 1000 - Date.now()))
+    );
+    try {
+      await unlock(await lpAccountOf(user.publicKey));
+      expect.fail("unlocking to an account not owned by the recipient should fail");
+    } catch (error: any) {
+      expect(error.toString()).to.include("Unauthorized");
+    }
+
+    const treasuryLp = await lpAccountOf(treasury.publicKey);
+    await unlock(treasuryLp);
+    expect((await getAccount(provider.connection, treasuryLp)).amount.toString()).to.equal(lockedAmount);
+    expect((await getAccount(provider.connection, pool.lpTokenAccount)).amount.toString()).to.equal("0");
+    const unlocked: any = await program.account.presale.fetch(locked.presale);
+    expect(unlocked.lpLockedAmount.toNumber()).to.equal(0);
+  });
 });